@@ -9,6 +9,13 @@ pub mod si;
 pub mod dg;
 pub mod dx;
 pub mod ca;
+pub mod at;
+pub mod fh;
+pub mod ev;
+pub mod ch;
+pub mod sr;
+pub mod md;
+pub mod hd;
 
 
 #[cfg(test)]
@@ -18,7 +25,7 @@ pub mod components_test {
     use crate::components::cc::conversion::*;
     use crate::components::dg::datagroup::DataGroup;
     use crate::components::ca::channelarray::ChannelArray;
-    use super::dx::dataxxx::{DataLink, VirtualBuf, DZBlock}; 
+    use super::dx::dataxxx::{DataLink, VirtualBuf, DZBlock, DT, read_data_block, read_data_block_with_cache, new_dz_cache}; 
     use rust_embed::RustEmbed;
     use std::io::Cursor;
     use std::fs::{File, self};
@@ -167,6 +174,258 @@ pub mod components_test {
         
     }
 
+    #[test]
+    fn test_cc_bitfield_text() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let (p, r, n, d) = (builder.add_text("P"), builder.add_text("R"), builder.add_text("N"), builder.add_text("D"));
+        let gear = builder.add_cc("Gear", 7, &[p, r, n, d, 0], &[0.0, 1.0, 2.0, 3.0]);
+        let active = builder.add_text("active");
+        let abs = builder.add_cc("ABS", 7, &[active, 0], &[4.0]);
+        let bitfield = builder.add_cc("", 11, &[gear, abs], &[f64::from_bits(0x03), f64::from_bits(0x04)]);
+        let broken = builder.add_cc("", 11, &[gear, active], &[f64::from_bits(0x03), f64::from_bits(0x04)]);   // TX instead of CC
+        let partial = builder.add_cc("", 11, &[gear, 0], &[f64::from_bits(0x03), f64::from_bits(0x0C)]);   // no sub conversion for upper bits
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let cc = Conversion::new(&mut buf, bitfield).unwrap();
+        assert!(!cc.get_cc_type().is_num());
+        assert_eq!(cc.convert_to_mix(&mut buf, 0x07).unwrap().into_string().unwrap(), "Gear=D|ABS=active");
+        assert_eq!(cc.convert_to_mix(&mut buf, 0x01).unwrap().into_string().unwrap(), "Gear=R");
+        assert!(Conversion::new(&mut buf, broken).is_err());
+        let cc = Conversion::new(&mut buf, partial).unwrap();
+        assert_eq!(cc.convert_to_mix(&mut buf, 0x07).unwrap().into_string().unwrap(), "Gear=D|4");
+        assert!(Conversion::new(&mut buf, 0).unwrap().convert_to_mix(&mut buf, 1.0).is_err());   // 1:1 conversion has no text
+    }
+
+    #[test]
+    fn test_cc_inverse() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let inverse = builder.add_cc("", 1, &[], &[-0.5, 0.5]);
+        let linear = builder.add_cc("", 1, &[], &[1.0, 2.0]);
+        builder.set_link(linear, 3, inverse);
+        builder.set_link(inverse, 3, linear);
+        let rational = builder.add_cc("", 2, &[], &[0.0, 4.0, 2.0, 0.0, 0.0, 1.0]);
+        let table = builder.add_cc("", 4, &[], &[0.0, 10.0, 10.0, 30.0]);
+        let (off, on) = (builder.add_text("off"), builder.add_text("on"));
+        let text = builder.add_cc("", 7, &[off, on, 0], &[0.0, 1.0]);
+        let formula = builder.add_text("X +");
+        let broken_inverse = builder.add_cc("", 3, &[formula], &[]);
+        let with_broken_inverse = builder.add_cc("", 1, &[], &[1.0, 2.0]);
+        builder.set_link(with_broken_inverse, 3, broken_inverse);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+
+        let cc = Conversion::new(&mut buf, linear).unwrap();
+        assert!(cc.is_inverse());
+        assert!(cc.get_inverse().unwrap().get_inverse().is_none());
+        assert_eq!(cc.convert_phys_to_raw(5.0).unwrap(), 2.0);
+        let cc = Conversion::new(&mut buf, rational).unwrap();
+        assert_eq!(cc.convert_phys_to_raw(10.0).unwrap(), 2.0);
+        let cc = Conversion::new(&mut buf, table).unwrap();
+        assert_eq!(cc.convert_phys_to_raw(20.0).unwrap(), 5.0);
+        assert_eq!(cc.convert_num_value::<f64, f64>(5.0), 20.0);
+        let cc = Conversion::new(&mut buf, text).unwrap();
+        assert_eq!(cc.convert_text_to_raw(&mut buf, "on").unwrap(), 1.0);
+        assert!(cc.convert_text_to_raw(&mut buf, "unknown").is_err());
+        assert!(cc.convert_phys_to_raw(1.0).is_err());
+        let cc = Conversion::new(&mut buf, with_broken_inverse).unwrap();
+        assert!(matches!(cc.convert_phys_to_raw(5.0), Err(crate::error::Mf4Error::Conversion { .. })));   // no NaN from the inverse
+    }
+
+    #[test]
+    fn test_cc_v3_tables() {
+        use crate::error::Mf4Error;
+        fn cc_v3(cc_type: u16, entries: &[(f64, f64)]) -> Vec<u8> {
+            let mut file: Vec<u8> = vec![0u8; 64];
+            let size = 46 + entries.len() * 16;
+            file.extend_from_slice(b"CC");
+            file.extend_from_slice(&(size as u16).to_le_bytes());
+            file.resize(64 + 42, 0);
+            file.extend_from_slice(&cc_type.to_le_bytes());
+            file.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+            entries.iter().for_each(|(k, v)| {
+                file.extend_from_slice(&k.to_le_bytes());
+                file.extend_from_slice(&v.to_le_bytes());
+            });
+            file
+        }
+        let data = cc_v3(1, &[(0.0, 10.0), (10.0, 30.0)]);
+        let cc = Conversion::new_v3(&mut Cursor::new(data.as_slice()), 64).unwrap();
+        assert_eq!(cc.convert_num_value::<f64, f64>(5.0), 20.0);
+        assert_eq!(cc.convert_phys_to_raw(20.0).unwrap(), 5.0);
+        for cc_type in [1, 2] {   // tables without entries can not be evaluated
+            let data = cc_v3(cc_type, &[]);
+            let err = Conversion::new_v3(&mut Cursor::new(data.as_slice()), 64).err().unwrap();
+            assert!(matches!(err, Mf4Error::Malformed { offset: Some(64), .. }));
+        }
+    }
+
+    #[test]
+    fn test_cc_unsupported() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let unknown = builder.add_cc("", 20, &[], &[]);
+        let bad_linear = builder.add_cc("", 1, &[], &[1.0, 2.0, 3.0]);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let err = Conversion::new(&mut buf, unknown).err().unwrap();
+        assert!(matches!(err, Mf4Error::Unsupported { offset: Some(off), .. } if off == unknown));
+        let err = Conversion::new(&mut buf, bad_linear).err().unwrap();
+        assert!(matches!(err, Mf4Error::Conversion { offset: Some(off), .. } if off == bad_linear));
+    }
+
+    #[test]
+    fn test_typed_errors() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let cn = builder.add_cn("scalar", 0, 0, 0, 0, 8, 0, 0);
+        let formula = builder.add_text("X * 2");
+        let algebraic = builder.add_cc("", 3, &[formula], &[]);
+        let bad_cg = builder.add_cg(algebraic, 0, 1, 0, 1, 0);   // cg_cn_first points to a CC block
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let channel = Channel::new(&mut buf, cn).unwrap();
+        assert!(matches!(channel.generate_template_element_channels(), Err(Mf4Error::Unsupported { .. })));
+        assert!(matches!(channel.generate_composed_channels(), Err(Mf4Error::Unsupported { .. })));
+        assert!(matches!(channel.generate_array_element_channel(), Err(Mf4Error::Unsupported { .. })));
+        let cc = Conversion::new(&mut buf, algebraic).unwrap();
+        assert!(matches!(cc.convert_phys_to_raw(1.0), Err(Mf4Error::Conversion { .. })));
+        let err = ChannelGroup::new(&mut buf, bad_cg).err().unwrap();
+        assert!(matches!(err, Mf4Error::CorruptLink { target, offset: Some(_) } if target == algebraic));
+    }
+
+    #[test]
+    fn test_md_comment_parse() {
+        use super::md::metadata::{MdComment, CommentKind};
+        let md = MdComment::parse("<SIcomment xmlns=\"http://www.asam.net/mdf/v4\"><TX>CAN &amp; LIN</TX>\
+            <names><name>Gateway</name><display>GW</display></names><path>Vehicle.Bus</path><bus>CAN1</bus><protocol>J1939</protocol>\
+            <common_properties><e name=\"version\">1.0</e><tree name=\"ecu\"><e name=\"id\">7</e></tree>\
+            <list name=\"channels\"><li><e name=\"rate\">10</e></li><li><e name=\"rate\">20</e></li></list>\
+            <elist name=\"tags\"><eli>a</eli><eli>b</eli></elist></common_properties></SIcomment>");
+        assert_eq!(md.get_kind(), &CommentKind::SI);
+        assert_eq!(md.get_text(), "CAN & LIN");
+        assert_eq!(md.get_names().get("name").unwrap(), "Gateway");
+        assert_eq!(md.get_display_name(), Some("GW"));
+        assert_eq!(md.get_field("path"), Some("Vehicle.Bus"));
+        assert_eq!(md.get_field("bus"), Some("CAN1"));
+        assert_eq!(md.get_property("version"), Some("1.0"));
+        assert_eq!(md.get_property("ecu/id"), Some("7"));
+        assert_eq!(md.get_property("channels/1/rate"), Some("20"));
+        assert_eq!(md.get_property("tags/0"), Some("a"));
+        let plain = MdComment::parse("just a TX comment\0");
+        assert_eq!(plain.get_kind(), &CommentKind::Plain);
+        assert_eq!(plain.get_text(), "just a TX comment");
+        assert!(plain.get_properties().is_empty());
+    }
+
+    #[test]
+    fn test_dz_transposed() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let records: Vec<u8> = (0..83u8).collect();  // 10 records of 8 bytes and 3 remaining bytes
+        let dz = builder.add_dz("DT", 1, 8, &records);
+        let dz_plain = builder.add_dz("DT", 0, 0, &records);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let block = DZBlock::new(&mut buf, dz_plain).unwrap();
+        assert_eq!(block.get_data(&mut buf).unwrap().as_slice(), records.as_slice());
+        let block = read_data_block(&mut buf, dz).unwrap();
+        let mut data_buf = [0u8; 5];
+        block.read_virtual_buf(&mut buf, 78, &mut data_buf).unwrap();
+        assert_eq!(data_buf, [78, 79, 80, 81, 82]);
+        let mut values: Vec<u8> = Vec::new();
+        block.for_each_strided(&mut buf, 2, 8, 1, 10, &mut |piece| {
+            values.extend_from_slice(piece);
+            Ok(())
+        }).unwrap();
+        assert_eq!(values, (0..10u8).map(|i| i * 8 + 2).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_dt_bounds() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let dt = builder.add_block("DT", &[], &[1, 2]);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(&data[..dt as usize + 26]);   // block at the end of the file
+        let block = DT::new(&mut buf, dt).unwrap();
+        assert_eq!(block.get_data_ref(&mut buf, 0, 2).unwrap().as_ref(), &[1, 2]);
+        assert!(block.get_data_ref(&mut buf, 1, 4).is_err());
+        assert!(block.read_virtual_buf(&mut buf, 5, &mut [0u8; 1]).is_err());
+        assert!(block.for_each_strided(&mut buf, 0, 1, 1, 3, &mut |_| Ok(())).is_err());
+        let mut patched = data[..dt as usize + 26].to_vec();
+        patched[dt as usize + 8..dt as usize + 16].copy_from_slice(&34u64.to_le_bytes());   // block claims 10 bytes
+        let mut buf = Cursor::new(patched.as_slice());
+        let block = DT::new(&mut buf, dt).unwrap();
+        assert!(block.get_data_ref(&mut buf, 0, 8).is_err());
+    }
+
+    #[test]
+    fn test_dz_malformed() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let header = |ori_len: u64, len: u64| -> Vec<u8> {   // DZ data section without compressed bytes
+            let mut d: Vec<u8> = b"DT".to_vec();
+            d.extend_from_slice(&[0u8; 6]);
+            d.extend_from_slice(&ori_len.to_le_bytes());
+            d.extend_from_slice(&len.to_le_bytes());
+            d
+        };
+        let huge_len = builder.add_block("DZ", &[], &header(4, u64::MAX - 8));
+        let dz = builder.add_dz("DT", 0, 0, &[1, 2, 3, 4]);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let err = DZBlock::new(&mut buf, huge_len).err().unwrap();
+        assert!(matches!(err, Mf4Error::Malformed { offset: Some(off), .. } if off == huge_len));
+        assert!(matches!(DZBlock::new(&mut buf, huge_len + 8), Err(Mf4Error::CorruptLink { .. })));
+        // original length far beyond the compressed content
+        let mut patched = data.clone();
+        patched[dz as usize + 32..dz as usize + 40].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut buf = Cursor::new(patched.as_slice());
+        let block = DZBlock::new(&mut buf, dz).unwrap();
+        let err = block.get_data(&mut buf).err().unwrap();
+        assert!(matches!(err, Mf4Error::Malformed { offset: Some(off), .. } if off == dz));
+        // unknown zip type
+        let mut patched = data.clone();
+        patched[dz as usize + 26] = 5;
+        let mut buf = Cursor::new(patched.as_slice());
+        let err = DZBlock::new(&mut buf, dz).err().unwrap();
+        assert!(matches!(err, Mf4Error::Unsupported { offset: Some(off), .. } if off == dz));
+    }
+
+    #[test]
+    fn test_dz_cache() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let first: Vec<u8> = (0..100u8).collect();
+        let second: Vec<u8> = (100..200u8).collect();
+        let dz1 = builder.add_dz("DT", 0, 0, &first);
+        let dz2 = builder.add_dz("DT", 1, 10, &second);
+        let mut dl_data: Vec<u8> = vec![0x01, 0, 0, 0];
+        dl_data.extend_from_slice(&2u32.to_le_bytes());
+        dl_data.extend_from_slice(&100u64.to_le_bytes());
+        let dl = builder.add_block("DL", &[0, dz1, dz2], &dl_data);
+        let data = builder.finish(0);
+        let mut buf = Cursor::new(data.as_slice());
+        let cache = new_dz_cache(1);
+        let block = read_data_block_with_cache(&mut buf, dl, &cache).unwrap();
+        assert!(cache.lock().unwrap().is_empty());   // nothing is decompressed while opening
+        assert_eq!(block.get_data_len(), 200);
+        let mut data_buf = [0u8; 4];
+        block.read_virtual_buf(&mut buf, 98, &mut data_buf).unwrap();   // spans two blocks
+        assert_eq!(data_buf, [98, 99, 100, 101]);
+        assert_eq!(cache.lock().unwrap().len(), 1);
+        block.read_virtual_buf(&mut buf, 10, &mut data_buf).unwrap();
+        assert_eq!(data_buf, [10, 11, 12, 13]);
+        assert_eq!(block.get_data_ref(&mut buf, 196, 4).unwrap().as_ref(), &[196, 197, 198, 199]);
+        assert_eq!(cache.lock().unwrap().len(), 1);
+        let mut pieces: Vec<Vec<u8>> = Vec::new();
+        block.for_each_strided(&mut buf, 0, 7, 4, 28, &mut |piece| {   // piece 14 spans both blocks
+            pieces.push(piece.to_vec());
+            Ok(())
+        }).unwrap();
+        let expected: Vec<Vec<u8>> = (0..28u8).map(|i| (i * 7..i * 7 + 4).collect()).collect();
+        assert_eq!(pieces, expected);
+        assert_eq!(cache.lock().unwrap().len(), 1);
+        assert!(block.for_each_strided(&mut buf, 0, 7, 4, 30, &mut |_| Ok(())).is_err());
+    }
+
     #[rstest]
     fn test_dl_new_0(buffer: &Mutex<Cursor<&[u8]>>) {
         let offset: u64 = 0xdbc0;
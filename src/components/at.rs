@@ -0,0 +1,142 @@
+pub mod attachment {
+    use std::io::{Cursor, Read};
+    use std::path::{Path, PathBuf};
+    use flate2::bufread::ZlibDecoder;
+    use crate::block::BlockInfo;
+    use crate::parser::{get_clean_text, get_block_desc_by_name};
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Attachment {
+        offset: u64,   // offset of AT block; referenced by synchronization channels
+        file_name: String,
+        mime_type: String,
+        comment: String,
+        flags: u16,
+        creator_index: u16,   // index of FH block which created this attachment
+        md5_checksum: [u8; 16],
+        original_size: u64,
+        embedded_size: u64,
+        data_offset: u64,  // absolute offset of embedded data in file
+    }
+
+    impl Attachment {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let at_desc = get_block_desc_by_name("AT".to_string()).ok_or("AT block not found")?;
+            let info: BlockInfo = at_desc.try_parse_buf(buf, offset)?;
+            let file_name: String = get_clean_text(buf, info.get_link_offset_normal("at_tx_filename").unwrap_or(0))
+                                    .unwrap_or("".to_string());
+            let mime_type: String = get_clean_text(buf, info.get_link_offset_normal("at_tx_mimetype").unwrap_or(0))
+                                    .unwrap_or("".to_string());
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("at_md_comment").unwrap_or(0))
+                                    .unwrap_or("".to_string());
+            let flags: u16 = info.get_data_value_first("at_flags").ok_or("at_flags not found")?;
+            let creator_index: u16 = info.get_data_value_first("at_creator_index").ok_or("at_creator_index not found")?;
+            let md5: Vec<u8> = info.get_data_value_copy("at_md5_checksum").ok_or("at_md5_checksum not found")?.try_into()?;
+            let original_size: u64 = info.get_data_value_first("at_original_size").ok_or("at_original_size not found")?;
+            let embedded_size: u64 = info.get_data_value_first("at_embedded_size").ok_or("at_embedded_size not found")?;
+            Ok(Self {
+                offset,
+                file_name,
+                mime_type,
+                comment,
+                flags,
+                creator_index,
+                md5_checksum: md5.try_into().map_err(|_| "Invalid md5 checksum length")?,
+                original_size,
+                embedded_size,
+                data_offset: offset + 24 + info.links.len() as u64 * 8 + 40,  // header + links + fixed data fields
+            })
+        }
+
+        pub fn get_offset(&self) -> u64 {
+            self.offset
+        }
+
+        pub fn get_file_name(&self) -> &str {
+            &self.file_name
+        }
+
+        pub fn get_mime_type(&self) -> &str {
+            &self.mime_type
+        }
+
+        pub fn get_comment(&self) -> &str {
+            &self.comment
+        }
+
+        pub fn get_creator_index(&self) -> u16 {
+            self.creator_index
+        }
+
+        pub fn is_embedded(&self) -> bool {
+            self.flags & 0x01 != 0
+        }
+
+        pub fn is_compressed(&self) -> bool {
+            self.flags & 0x02 != 0
+        }
+
+        pub fn has_md5(&self) -> bool {
+            self.flags & 0x04 != 0
+        }
+
+        pub fn get_md5(&self) -> Option<&[u8; 16]> {
+            if self.has_md5() { Some(&self.md5_checksum) } else { None }
+        }
+
+        pub fn get_original_size(&self) -> u64 {
+            self.original_size
+        }
+
+        pub fn get_embedded_size(&self) -> u64 {
+            self.embedded_size
+        }
+
+        pub fn get_data(&self, buf: &mut Cursor<&[u8]>) -> Result<Vec<u8>, Mf4Error> {
+            /* payload of embedded attachment; compressed data is inflated */
+            if !self.is_embedded() {
+                return Err(Mf4Error::unsupported(format!("embedded data of external attachment {}", self.file_name)).with_offset(self.offset));
+            }
+            let file: &[u8] = buf.get_ref();
+            let raw: &[u8] = usize::try_from(self.data_offset).ok()
+                                 .zip(usize::try_from(self.embedded_size).ok())
+                                 .and_then(|(start, size)| file.get(start..start.checked_add(size)?))
+                                 .ok_or_else(|| Mf4Error::Malformed {
+                                     reason: format!("Embedded data of attachment {} out of file range", self.file_name),
+                                     offset: Some(self.offset),
+                                 })?;
+            if self.is_compressed() {
+                let mut decoder: ZlibDecoder<&[u8]> = ZlibDecoder::new(raw);
+                let mut data: Vec<u8> = Vec::new();   // at_original_size is not trusted for preallocation
+                decoder.read_to_end(&mut data)?;
+                Ok(data)
+            } else {
+                Ok(raw.to_vec())
+            }
+        }
+
+        pub fn check_md5(&self, data: &[u8]) -> bool {
+            // true if the checksum matches or there is no checksum to verify
+            !self.has_md5() || md5::compute(data).0 == self.md5_checksum
+        }
+
+        pub fn resolve_path(&self, mf4_path: &Path) -> PathBuf {
+            /* external file name is relative to the mf4 file if it is not absolute */
+            let path = PathBuf::from(&self.file_name);
+            if path.is_absolute() {
+                path
+            } else {
+                mf4_path.parent().unwrap_or(Path::new("")).join(path)
+            }
+        }
+
+        pub fn read_external(&self, mf4_path: &Path) -> Result<Vec<u8>, Mf4Error> {
+            if self.is_embedded() {
+                return Err(Mf4Error::unsupported(format!("external file of embedded attachment {}", self.file_name)).with_offset(self.offset));
+            }
+            Ok(std::fs::read(self.resolve_path(mf4_path))?)
+        }
+    }
+}
@@ -1,8 +1,10 @@
 pub mod channelarray {
+    use crate::error::Mf4Error;
     use std::io::{Cursor, Read};
 
     use crate::parser::get_block_desc_by_name;
     use crate::data_serde::DataValue;
+    use crate::components::cc::conversion::Conversion;
 
     #[allow(dead_code)]
     #[derive(Debug, Clone)]
@@ -15,12 +17,73 @@ pub mod channelarray {
         ca_inval_bit_pos_base: u32,
         ca_dim_size: Vec<u64>,
         f_vec: Vec<usize>,
+        g_vec: Vec<usize>,   // for invalidation bit positions
         row_oriented: bool,
+        ca_cycle_count: Vec<u64>,  // cycle count of each element; only for CG and DG template
+        ca_data: Vec<u64>,  // data block (DT/DL/DZ/HL/LD) with the records of each element; only for DG template
+        axis_values: Vec<Vec<f64>>,  // fixed axis values for each dimension
+        axis_links: Vec<(u64, u64, u64)>,  // (DG, CG, CN) of axis channel for each dimension
+        axis_conversions: Vec<Option<Conversion>>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ArrayType {
+        Array,
+        ScalingAxis,
+        LookUp,
+        IntervalAxis,
+        ClassificationResult,
+        Unknown(u8),
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum AxisSource {
+        Index,  // no axis; element index is used
+        Fixed(Vec<f64>),
+        Channel(u64, u64, u64),  // (DG, CG, CN) offsets of axis channel
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct ArrayAxis {
+        pub name: String,  // name of axis channel; empty for fixed or default axis
+        pub values: Vec<f64>,  // physical axis values
+        pub conversion: Option<Conversion>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct ArrayValue {
+        /* N-dimensional array of each record, e.g. a calibration curve or map with its axes */
+        pub name: String,
+        pub array_type: ArrayType,
+        pub shape: Vec<usize>,
+        pub axes: Vec<ArrayAxis>,  // one axis for each dimension
+        pub values: Vec<Vec<f64>>,  // values of each record flattened in row-major order
+    }
+
+    impl ArrayValue {
+        pub fn get(&self, record: usize, index: &[usize]) -> Option<f64> {
+            if index.len() != self.shape.len() || index.iter().zip(self.shape.iter()).any(|(i, n)| i >= n) {
+                return None
+            }
+            let pos: usize = index.iter().zip(self.shape.iter()).fold(0, |acc, (i, n)| acc * n + i);
+            self.values.get(record)?.get(pos).copied()
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum ArrayStorage {
+        CnTemplate,  // all elements in the same record
+        CgTemplate,  // each element has its own record id in the same data group
+        DgTemplate,  // records of each element are stored in their own data block
     }
 
     impl ChannelArray {
-        pub fn new(file: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Box<dyn std::error::Error>> {
-            let block_desc = get_block_desc_by_name("CA".to_string()).unwrap();
+        pub fn new(file: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_inner(file, offset).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_inner(file: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let block_desc = get_block_desc_by_name("CA".to_string()).ok_or("CA block not found")?;
             let block_info = block_desc.try_parse_buf(file, offset)?;
             let ca_type = block_info.get_data_value_first("ca_type").ok_or("Failed to get ca_type")?;
             let ca_storage = block_info.get_data_value_first("ca_storage").ok_or("Failed to get ca_storage")?;
@@ -29,26 +92,46 @@ pub mod channelarray {
             let ca_byte_offset_base = block_info.get_data_value_first("ca_byte_offset_base").ok_or("Failed to get ca_byte_offset_base")?;
             let ca_inval_bit_pos_base = block_info.get_data_value_first("ca_inval_bit_pos_base").ok_or("Failed to get ca_inval_bit_pos_base")?;
             let mut ca_dim_size = Vec::new();
+            let mut ca_cycle_count = Vec::new();
+            let mut axis_values: Vec<Vec<f64>> = Vec::new();
             if let DataValue::BYTE(bytes) = block_info.get_data_value("unparsed_data").ok_or("Invalid ca data without dim size")? {
                 let mut cur = Cursor::new(bytes);
+                let mut eight_bytes = [0u8; 8];
                 for _ in 0..ca_ndim {
-                    let mut eight_bytes = [0u8; 8];
                     cur.read_exact(&mut eight_bytes)?;
                     ca_dim_size.push(u64::from_le_bytes(eight_bytes));
                 }
-            }
-            let row_oriented = ca_flags & (0x01 << 6) != (0x01 << 6); // bit 6
-            let mut f_vec: Vec<usize> = vec![ca_byte_offset_base as usize];
-            if row_oriented {
-                for i in 0..(ca_ndim-1) {
-                    f_vec.push(f_vec.last().unwrap() * (ca_dim_size[(ca_ndim-1-i) as usize]) as usize);
+                if ca_flags & 0x20 != 0 {  // fixed axis values come before the cycle counts
+                    for dim in ca_dim_size.iter() {
+                        let mut values: Vec<f64> = Vec::with_capacity(*dim as usize);
+                        for _ in 0..*dim {
+                            cur.read_exact(&mut eight_bytes)?;
+                            values.push(f64::from_le_bytes(eight_bytes));
+                        }
+                        axis_values.push(values);
+                    }
                 }
-                f_vec.reverse();
-            } else {
-                for i in 0..(ca_ndim-1) {
-                    f_vec.push(f_vec.last().unwrap() * (ca_dim_size[i as usize]) as usize);
+                if ca_storage != 0 {
+                    let elements: u64 = ca_dim_size.iter().product();
+                    for _ in 0..elements {
+                        cur.read_exact(&mut eight_bytes)?;
+                        ca_cycle_count.push(u64::from_le_bytes(eight_bytes));
+                    }
                 }
             }
+            let ca_data: Vec<u64> = block_info.get_link_offset_vec("ca_data").unwrap_or_default();
+            let axis_links: Vec<(u64, u64, u64)> = block_info.get_link_offset_vec("ca_axis").unwrap_or_default()
+                                                    .chunks_exact(3).map(|x| (x[0], x[1], x[2])).collect();
+            let mut axis_conversions: Vec<Option<Conversion>> = Vec::new();
+            for link in block_info.get_link_offset_vec("ca_cc_axis_conversion").unwrap_or_default() {
+                axis_conversions.push(if link != 0 { Some(Conversion::new(file, link)?) } else { None });
+            }
+            if ca_storage == 2 && ca_data.len() != ca_cycle_count.len() {
+                return Err("Invalid ca_data links for DG template".into());
+            }
+            let row_oriented = ca_flags & (0x01 << 6) != (0x01 << 6); // bit 6
+            let f_vec: Vec<usize> = Self::calculate_factors(ca_byte_offset_base as usize, &ca_dim_size, row_oriented);
+            let g_vec: Vec<usize> = Self::calculate_factors(ca_inval_bit_pos_base as usize, &ca_dim_size, row_oriented);
             Ok( Self {
                 ca_type,
                 ca_storage,
@@ -58,10 +141,33 @@ pub mod channelarray {
                 ca_inval_bit_pos_base,
                 ca_dim_size,
                 f_vec,
+                g_vec,
                 row_oriented,
+                ca_cycle_count,
+                ca_data,
+                axis_values,
+                axis_links,
+                axis_conversions,
             })
         }
 
+        fn calculate_factors(base: usize, ca_dim_size: &[u64], row_oriented: bool) -> Vec<usize> {
+            // factors for byte offsets and invalidation bit positions of array elements
+            let ca_ndim = ca_dim_size.len();
+            let mut factors: Vec<usize> = vec![base];
+            if row_oriented {
+                for i in 0..ca_ndim.saturating_sub(1) {
+                    factors.push(factors.last().unwrap() * ca_dim_size[ca_ndim-1-i] as usize);
+                }
+                factors.reverse();
+            } else {
+                for dim in ca_dim_size.iter().take(ca_ndim.saturating_sub(1)) {
+                    factors.push(factors.last().unwrap() * *dim as usize);
+                }
+            }
+            factors
+        }
+
         pub fn generate_array_names(&self, channel_name: &str) -> Vec<String> {
             let mut array_names = Vec::new();
             fn dfs_gen(prefix: &mut String, array: &ChannelArray, n_dim: u64, res: &mut Vec<String>) {
@@ -106,7 +212,7 @@ pub mod channelarray {
             array_indexs
         }
 
-        pub fn calculate_byte_offset(&self, index: &Vec<usize>) -> Result<u32, Box<dyn std::error::Error>> {
+        pub fn calculate_byte_offset(&self, index: &Vec<usize>) -> Result<u32, Mf4Error> {
             if index.len() != self.ca_ndim as usize {
                 return Err("Invalid index array length for CA".into());
             } else {
@@ -114,6 +220,69 @@ pub mod channelarray {
             }
         }
 
+        pub fn calculate_inval_bit_pos(&self, index: &[usize]) -> Result<u32, Mf4Error> {
+            if index.len() != self.ca_ndim as usize {
+                Err("Invalid index array length for CA".into())
+            } else {
+                Ok(index.iter().zip(self.g_vec.iter()).fold(0, |acc, y| acc + (y.1 * y.0) as u32))
+            }
+        }
+
+        pub fn calculate_linear_index(&self, index: &[usize]) -> Result<usize, Mf4Error> {
+            // position of an element in ca_cycle_count and ca_data; follows the storage order of the array
+            if index.len() != self.ca_ndim as usize {
+                Err("Invalid index array length for CA".into())
+            } else {
+                let factors: Vec<usize> = Self::calculate_factors(1, &self.ca_dim_size, self.row_oriented);
+                Ok(index.iter().zip(factors.iter()).fold(0, |acc, y| acc + y.1 * y.0))
+            }
+        }
+
+        pub fn get_type(&self) -> ArrayType {
+            match self.ca_type {
+                0 => ArrayType::Array,
+                1 => ArrayType::ScalingAxis,
+                2 => ArrayType::LookUp,
+                3 => ArrayType::IntervalAxis,
+                4 => ArrayType::ClassificationResult,
+                other => ArrayType::Unknown(other),
+            }
+        }
+
+        pub fn get_dim_sizes(&self) -> Vec<usize> {
+            self.ca_dim_size.iter().map(|x| *x as usize).collect()
+        }
+
+        pub fn get_axis_source(&self, dim: usize) -> AxisSource {
+            if let Some(values) = self.axis_values.get(dim) {
+                AxisSource::Fixed(values.clone())
+            } else if let Some((dg, cg, cn)) = self.axis_links.get(dim).filter(|x| x.2 != 0) {
+                AxisSource::Channel(*dg, *cg, *cn)
+            } else {
+                AxisSource::Index
+            }
+        }
+
+        pub fn get_axis_conversion(&self, dim: usize) -> Option<&Conversion> {
+            self.axis_conversions.get(dim)?.as_ref()
+        }
+
+        pub fn get_storage(&self) -> ArrayStorage {
+            match self.ca_storage {
+                1 => ArrayStorage::CgTemplate,
+                2 => ArrayStorage::DgTemplate,
+                _ => ArrayStorage::CnTemplate,
+            }
+        }
+
+        pub fn get_cycle_counts(&self) -> &Vec<u64> {
+            &self.ca_cycle_count
+        }
+
+        pub fn get_data_links(&self) -> &Vec<u64> {
+            &self.ca_data
+        }
+
         pub fn get_elements_num(&self) -> usize {
             self.ca_dim_size.iter().fold(1, |acc, y| acc * (*y as usize))
         }
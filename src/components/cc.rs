@@ -1,8 +1,10 @@
 pub mod conversion {
+    use crate::error::Mf4Error;
     use std::io::Cursor;
+    use crate::components::md::metadata::MdComment;
     use crate::block::BlockInfo;
-    use crate::data_serde::{DataValue, StringOrReal};
-    use crate::parser::{get_clean_text, get_block_desc_by_name, peek_block_type};
+    use crate::data_serde::{decode_text, DataValue, StringOrReal};
+    use crate::parser::{get_clean_text, get_clean_text_v3, get_block_desc_by_name, get_block_desc_v3_by_name, peek_block_type};
     use evalexpr::*;
 
     #[derive(Debug, Clone, Default)]
@@ -11,7 +13,8 @@ pub mod conversion {
         name: String,
         unit: String,
         comment: String,
-        inverse_ref: u64, // TODO: implement inverse conversion
+        inverse_ref: u64,
+        inverse: Option<Box<Conversion>>,  // parsed cc_cc_inverse block; its own inverse link is not followed
         cc_type: CcType,
     } 
     
@@ -29,7 +32,10 @@ pub mod conversion {
         ValueRange2Text((Vec<f64>, Vec<TextOrScale>)), 
         Text2Value((Vec<u64>, Vec<f64>)),
         Text2Text(Vec<u64>),
-        BitfieldText((Vec<u64>, Vec<u64>)),
+        BitfieldText((Vec<u64>, Vec<Conversion>)),  // masks and the value to text conversions applied to masked value
+        Polynomial([f64; 6]),    // MDF 3.x only
+        Exponential([f64; 7]),   // MDF 3.x only
+        Logarithmic([f64; 7]),   // MDF 3.x only
         NotImplemented   // and error condition
     }
 
@@ -51,6 +57,9 @@ pub mod conversion {
                 CcType::ValueRange(_) => true,
                 CcType::Text2Value(_) => true,
                 CcType::Algebraic(_) => true,
+                CcType::Polynomial(_) => true,
+                CcType::Exponential(_) => true,
+                CcType::Logarithmic(_) => true,
                 _ => false
             }
         }
@@ -63,26 +72,34 @@ pub mod conversion {
     }
 
     impl Conversion {
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Box<dyn std::error::Error>> {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_inner(buf, offset, true).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_inner(buf: &mut Cursor<&[u8]>, offset: u64, parse_inverse: bool) -> Result<Self, Mf4Error> {
             if offset == 0 {
                 return Ok(Self::default())    // allows default
             }
-            let cc_desc = get_block_desc_by_name("CC".to_string()).unwrap();
-            let block_info: BlockInfo = cc_desc.try_parse_buf(buf, offset).unwrap();
-            let name: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_tx_name").unwrap())
+            let cc_desc = get_block_desc_by_name("CC".to_string()).ok_or("CC block not found")?;
+            let block_info: BlockInfo = cc_desc.try_parse_buf(buf, offset)?;
+            let name: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_tx_name").unwrap_or(0))
                                 .unwrap_or("".to_string());
-            let unit: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_md_unit").unwrap())
+            let unit: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_md_unit").unwrap_or(0))
                                 .unwrap_or("".to_string());
-            let comment: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_md_comment").unwrap())
+            let comment: String = get_clean_text(buf, block_info.get_link_offset_normal("cc_md_comment").unwrap_or(0))
                                 .unwrap_or("".to_string());
-            let inverse_ref: u64 = block_info.get_link_offset_normal("cc_cc_inverse").unwrap(); // zero if not inverse
-            let cc_type_raw:u8 = block_info.get_data_value_first("cc_type").unwrap();
-            let mut cc_type = CcType::NotImplemented;
-            let cc_val: Vec<u64> = block_info.get_data_value("cc_val").unwrap().clone().try_into().unwrap();
-            let cc_val_count: u16 = block_info.get_data_value_first("cc_val_count").unwrap();
-            let cc_ref: Vec<u64> = block_info.get_link_offset_vec("cc_ref")
-                                            .unwrap_or(Vec::new()).clone().try_into().unwrap(); // could be Nil
-            let cc_ref_count: u16 = block_info.get_data_value_first("cc_ref_count").unwrap();
+            let inverse_ref: u64 = block_info.get_link_offset_normal("cc_cc_inverse").unwrap_or(0); // zero if not inverse
+            let inverse: Option<Box<Conversion>> = if parse_inverse && inverse_ref != 0 {
+                Some(Box::new(Self::new_inner(buf, inverse_ref, false).map_err(|e| e.with_offset(inverse_ref))?))   // inverse of inverse points back to self
+            } else {
+                None
+            };
+            let cc_type_raw:u8 = block_info.get_data_value_first("cc_type").ok_or("cc_type not found")?;
+            let cc_type: CcType;
+            let cc_val: Vec<u64> = block_info.get_data_value("cc_val").ok_or("cc_val not found")?.clone().try_into()?;
+            let cc_val_count: u16 = block_info.get_data_value_first("cc_val_count").ok_or("cc_val_count not found")?;
+            let cc_ref: Vec<u64> = block_info.get_link_offset_vec("cc_ref").unwrap_or_default(); // could be Nil
+            let cc_ref_count: u16 = block_info.get_data_value_first("cc_ref_count").ok_or("cc_ref_count not found")?;
             match cc_type_raw {
                 0 => { // one to one
                     cc_type = CcType::OneToOne;
@@ -100,7 +117,7 @@ pub mod conversion {
                     let text = get_clean_text(buf, cc_ref[0])?;
                     cc_type = CcType::Algebraic(text); 
                 },
-                4|5 if cc_val.len() == (cc_val_count) as usize && cc_val.len() % 2 == 0 => { // table
+                4|5 if cc_val.len() == (cc_val_count) as usize && cc_val.len() % 2 == 0 && cc_val_count > 0 => { // table
                     let mut key: Vec<f64> = Vec::new();
                     let mut value: Vec<f64> = Vec::new();
                     for i in 0..cc_val_count/2 {
@@ -113,7 +130,7 @@ pub mod conversion {
                         cc_type = CcType::Table((key, value));
                     }
                 },
-                6 if cc_val.len() == (cc_val_count) as usize => { // value range
+                6 if cc_val.len() == (cc_val_count) as usize && cc_val_count > 0 => { // value range
                     let value: Vec<f64> = cc_val.into_iter().map(|v| to_f64(v)).collect();  // consumed cc_val
                     cc_type = CcType::ValueRange(value);
                 },
@@ -172,11 +189,16 @@ pub mod conversion {
                 },
 
                 11 if cc_ref_count == cc_val_count && cc_val.len() == cc_val_count as usize && cc_ref.len() == cc_ref_count as usize => { // bitfield text
-                    cc_type = CcType::BitfieldText((cc_val, cc_ref));
-                }
-                _ => {
-                    println!("cc block {} has not support cc_type type {}", name, cc_type_raw);
+                    let mut sub_conversions: Vec<Conversion> = Vec::new();
+                    for link in cc_ref.into_iter() {
+                        sub_conversions.push(Conversion::new(buf, link)?);
+                    }
+                    cc_type = CcType::BitfieldText((cc_val, sub_conversions));
                 }
+                1..=11 => {  // known type whose values or references do not fit it
+                    return Err(Mf4Error::conversion(format!("invalid parameters for conversion type {}", cc_type_raw)))
+                },
+                _ => return Err(Mf4Error::unsupported(format!("conversion type {}", cc_type_raw))),
             }
                 
              
@@ -185,6 +207,79 @@ pub mod conversion {
                 unit,
                 comment,
                 inverse_ref,
+                inverse,
+                cc_type,
+            })
+        }
+
+        pub fn new_v3(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            /* MDF 3.x conversion; parameters are stored in the block itself instead of linked blocks */
+            Self::new_v3_inner(buf, offset).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_v3_inner(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            if offset == 0 {
+                return Ok(Self::default())    // allows default
+            }
+            let cc_desc = get_block_desc_v3_by_name("CC".to_string()).ok_or("CC block not found")?;
+            let block_info: BlockInfo = cc_desc.try_parse_buf_v3(buf, offset)?;
+            let unit: String = block_info.get_data_value_copy("cc_unit").ok_or("cc_unit not found")?.try_into()?;
+            let unit: String = unit.trim_end_matches('\0').to_string();
+            let cc_type_raw: u16 = block_info.get_data_value_first("cc_type").ok_or("cc_type not found")?;
+            let size_info: usize = block_info.get_data_value_first::<u16>("cc_size_info").ok_or("cc_size_info not found")? as usize;
+            let params: Vec<u8> = block_info.get_data_value_copy("unparsed_data")
+                                            .unwrap_or(DataValue::BYTE(Vec::new())).try_into()?;
+            let real = |pos: usize| -> f64 {
+                params.get(pos..pos+8).map(|b| f64::from_le_bytes(b.try_into().unwrap())).unwrap_or(0.0)
+            };
+            let text = |start: usize, len: usize| -> String {
+                params.get(start..start+len)
+                      .map(|b| decode_text(b).trim_end_matches('\0').to_string())
+                      .unwrap_or_default()
+            };
+            let cc_type: CcType = match cc_type_raw {
+                0 => CcType::Linear((real(0), real(8))),   // phys = P1 + P2 * int
+                1 | 2 if size_info == 0 => {
+                    return Err(Mf4Error::Malformed { reason: "conversion table without entries".to_string(), offset: Some(offset) });
+                },
+                1 | 2 => {
+                    let key: Vec<f64> = (0..size_info).map(|i| real(i*16)).collect();
+                    let value: Vec<f64> = (0..size_info).map(|i| real(i*16+8)).collect();
+                    if cc_type_raw == 1 { CcType::TableInt((key, value)) } else { CcType::Table((key, value)) }
+                },
+                6 => CcType::Polynomial([real(0), real(8), real(16), real(24), real(32), real(40)]),
+                7 => CcType::Exponential([real(0), real(8), real(16), real(24), real(32), real(40), real(48)]),
+                8 => CcType::Logarithmic([real(0), real(8), real(16), real(24), real(32), real(40), real(48)]),
+                9 => CcType::Rational([real(0), real(8), real(16), real(24), real(32), real(40)]),
+                10 => CcType::Algebraic(text(0, 256)),
+                11 => {  // value to text; each entry: REAL value + CHAR[32]
+                    let key: Vec<f64> = (0..size_info).map(|i| real(i*40)).collect();
+                    let mut texts: Vec<TextOrScale> = (0..size_info).map(|i| TextOrScale::Text(text(i*40+8, 32))).collect();
+                    texts.push(TextOrScale::Text("".to_string()));   // no default value in MDF 3.x
+                    CcType::Value2Text((key, texts))
+                },
+                12 if size_info > 0 => {  // value range to text; each entry: REAL lower + REAL upper + LINK to TX; first entry is default
+                    let mut value: Vec<f64> = Vec::new();
+                    let mut texts: Vec<TextOrScale> = Vec::new();
+                    for i in 1..size_info {
+                        value.push(real(i*20));
+                        value.push(real(i*20+8));
+                        let link = params.get(i*20+16..i*20+20).map(|b| u32::from_le_bytes(b.try_into().unwrap())).unwrap_or(0);
+                        texts.push(TextOrScale::Text(get_clean_text_v3(buf, link as u64).unwrap_or("".to_string())));
+                    }
+                    let link = params.get(16..20).map(|b| u32::from_le_bytes(b.try_into().unwrap())).unwrap_or(0);
+                    texts.push(TextOrScale::Text(get_clean_text_v3(buf, link as u64).unwrap_or("".to_string())));
+                    CcType::ValueRange2Text((value, texts))
+                },
+                65535 => CcType::OneToOne,
+                _ => return Err(Mf4Error::unsupported(format!("conversion type {}", cc_type_raw))),
+            };
+            Ok(Conversion {
+                name: "".to_string(),
+                unit,
+                comment: "".to_string(),
+                inverse_ref: 0,
+                inverse: None,
                 cc_type,
             })
         }
@@ -197,10 +292,19 @@ pub mod conversion {
             &self.comment
         }
 
+        pub fn get_md_comment(&self) -> MdComment {
+            // comment parsed from MD block xml
+            MdComment::parse(&self.comment)
+        }
+
         pub fn is_inverse(&self) -> bool {
             self.inverse_ref != 0
         }
 
+        pub fn get_inverse(&self) -> Option<&Conversion> {
+            self.inverse.as_deref()
+        }
+
         pub fn get_cc_name(&self) -> &str {
             &self.name
         }
@@ -211,75 +315,98 @@ pub mod conversion {
 
         pub fn convert_num_value<T, U>(&self, int: T) -> U 
         where T: Into<f64>, U: From<f64>{  // intermediate calculation use f64
+            U::from(self.try_convert_num_value(int).unwrap_or(f64::NAN))   // NaN if the value can not be converted
+        }
+
+        pub fn try_convert_num_value<T>(&self, int: T) -> Result<f64, Mf4Error> 
+        where T: Into<f64> {
             let inp: f64 = int.into();
             match &self.cc_type {
                 CcType::OneToOne => {
-                    U::from(inp)
+                    Ok(inp)
                 },
                 CcType::Linear(v) => {
-                    U::from(v.0 + v.1 * inp)
+                    Ok(v.0 + v.1 * inp)
                 },
                 CcType::Rational(v) => {
                     let numerator = v[0] * inp * inp + v[1]* inp + v[2];
                     let denominator = v[3] * inp * inp + v[4] * inp + v[5];
-                    U::from(numerator / denominator)
+                    Ok(numerator / denominator)
                 },
                 CcType::TableInt((index, value)) => {
                     let mut right_ind: usize = 0;
-                    while inp >= index[right_ind] && right_ind < index.len() {
+                    while right_ind < index.len() && inp >= index[right_ind] {
                         right_ind += 1;
                     };
                     if right_ind == 0 {
-                        U::from(value[0])
+                        Ok(value[0])
                     } else if right_ind == index.len() {
-                        U::from(value[value.len()-1])
+                        Ok(value[value.len()-1])
                     } else {
                         let left_val = value[right_ind-1];
                         let right_val = value[right_ind];
                         let left_ind_val = index[right_ind-1];
                         let right_ind_val = index[right_ind];
                         let ratio = (right_val - left_val) / (right_ind_val - left_ind_val);
-                        U::from(left_val + ratio * (inp - left_ind_val))
+                        Ok(left_val + ratio * (inp - left_ind_val))
                     }
                 },
                 CcType::Table((index, value)) => {
                     if inp <= index[0] {
-                        U::from(value[0])
+                        Ok(value[0])
                     } else if inp >= index[index.len()-1] {
-                        U::from(value[value.len()-1])
+                        Ok(value[value.len()-1])
                     } else {
                         let mut right_ind = 0;
                         while right_ind < index.len() && inp >= index[right_ind]{
                             right_ind += 1;
                         };
                         let left_val = value[right_ind-1];
-                        U::from(left_val)
+                        Ok(left_val)
                     }
                 },
                 CcType::ValueRange(value) => {
-                    let default_value = value.last().unwrap();
+                    let default_value = value.last().ok_or(Mf4Error::conversion("empty value range"))?;
                     let mut left_ind = 0;
                     while left_ind < value.len()-1 && inp >= value[left_ind] {
                         left_ind += 3;
                     };
                     if left_ind == 0 || left_ind >= value.len()-1 {
-                        U::from(default_value.to_owned())
+                        Ok(default_value.to_owned())
                     } else {
-                        U::from(value[left_ind-1])
+                        Ok(value[left_ind-1])
+                    }
+                },
+                CcType::Polynomial(p) => {
+                    let x = inp - p[4] - p[5];
+                    Ok((p[1] - p[3] * x) / (p[2] * x - p[0]))
+                },
+                CcType::Exponential(p) => {
+                    if p[3] == 0.0 {
+                        Ok((((inp - p[6]) * p[5] - p[2]) / p[0]).ln() / p[1])
+                    } else {
+                        Ok(((p[2] / (inp - p[6]) - p[5]) / p[3]).ln() / p[4])
+                    }
+                },
+                CcType::Logarithmic(p) => {
+                    if p[3] == 0.0 {
+                        Ok((((inp - p[6]) * p[5] - p[2]) / p[0]).exp() / p[1])
+                    } else {
+                        Ok(((p[2] / (inp - p[6]) - p[5]) / p[3]).exp() / p[4])
                     }
                 },
                 CcType::Algebraic(text) => {
-                    let context = context_map! {"X" => inp}.unwrap();
-                    let value = eval_float_with_context(&text, &context).unwrap();
-                    U::from(value)
+                    let context = context_map! {"X" => inp, "X1" => inp}?;   // X1 is used by MDF 3.x formulas
+                    let value = eval_float_with_context(&text, &context)?;
+                    Ok(value)
                 }
                 _ => {
-                    panic!("cc block {} has not support cc_type", self.name);
+                    Err(Mf4Error::conversion(format!("cc block {} is not a numerical conversion", self.name)))
                 }
             }
         }
 
-        pub fn convert_to_mix<T>(&self, buf: &mut Cursor<&[u8]>,int: T) -> Result<StringOrReal, Box<dyn std::error::Error>> 
+        pub fn convert_to_mix<T>(&self, buf: &mut Cursor<&[u8]>,int: T) -> Result<StringOrReal, Mf4Error> 
         where T: Into<f64> {
             let inp: f64 = int.into();
             match &self.cc_type {
@@ -309,13 +436,36 @@ pub mod conversion {
                         Self::to_mix(&default_value, inp, buf)
                     }
                 },
-                _ => {
-                        panic!("cc block {} has not support cc_type", self.name);
+                CcType::BitfieldText((masks, conversions)) => {
+                    // each mask selects some bits, the masked value is translated by the sub conversion
+                    let raw: u64 = inp as u64;
+                    let mut parts: Vec<String> = Vec::new();
+                    for (mask, conv) in masks.iter().zip(conversions.iter()) {
+                        let masked: f64 = (raw & mask) as f64;
+                        let text = if matches!(conv.cc_type, CcType::OneToOne) {
+                            masked.to_string()   // sub conversion is missing (link 0), masked value is shown as it is
+                        } else {
+                            match conv.convert_to_mix(buf, masked)? {
+                                StringOrReal::String(s) => s,
+                                StringOrReal::Real(f) => f.to_string(),
+                            }
+                        };
+                        if text.is_empty() {
+                            continue;  // nothing to show for this bit group
+                        }
+                        if conv.name.is_empty() {
+                            parts.push(text);
+                        } else {
+                            parts.push(format!("{}={}", conv.name, text));
+                        }
                     }
+                    Ok(StringOrReal::String(parts.join("|")))
+                },
+                _ => Err(Mf4Error::conversion(format!("cc block {} can not convert to text", self.name))),
             }
         }
 
-        fn to_mix<T>(conv: &TextOrScale, inp: T, buf: &mut Cursor<&[u8]>) -> Result<StringOrReal,  Box<dyn std::error::Error>> 
+        fn to_mix<T>(conv: &TextOrScale, inp: T, buf: &mut Cursor<&[u8]>) -> Result<StringOrReal,  Mf4Error> 
         where T: Into<f64>{
             match conv {
                 TextOrScale::Text(text) => Ok(StringOrReal::String(text.clone())),
@@ -324,14 +474,111 @@ pub mod conversion {
                         let num: f64 = conv.convert_num_value(inp);
                         Ok(StringOrReal::Real(num))
                     } else {
-                        let mix: Result<StringOrReal, Box<dyn std::error::Error>> = conv.convert_to_mix(buf, inp);
+                        let mix: Result<StringOrReal, Mf4Error> = conv.convert_to_mix(buf, inp);
                         mix   //  for debug purpose
                     }
                 },
             }
         }
 
-        pub fn convert_from_text(&self, buf: &mut Cursor<&[u8]>, inp: &Vec<String>) -> Result<DataValue, Box<dyn std::error::Error>> {
+        pub fn convert_phys_to_raw(&self, phys: f64) -> Result<f64, Mf4Error> {
+            /* physical value back to raw value; the inverse cc block is preferred when it is given */
+            if let Some(inverse) = &self.inverse {
+                if inverse.cc_type.is_num() {
+                    return inverse.try_convert_num_value(phys);
+                }
+            }
+            match &self.cc_type {
+                CcType::OneToOne => Ok(phys),
+                CcType::Linear((a, b)) => {
+                    if *b == 0.0 {
+                        Err(Mf4Error::conversion("linear conversion with zero factor can not be inverted"))
+                    } else {
+                        Ok((phys - a) / b)
+                    }
+                },
+                CcType::Rational(v) => {
+                    // phys * (v3*x^2 + v4*x + v5) = v0*x^2 + v1*x + v2
+                    let a = v[0] - phys * v[3];
+                    let b = v[1] - phys * v[4];
+                    let c = v[2] - phys * v[5];
+                    if a == 0.0 {
+                        if b == 0.0 {
+                            Err(Mf4Error::conversion("rational conversion can not be inverted"))
+                        } else {
+                            Ok(-c / b)
+                        }
+                    } else {
+                        let delta = b * b - 4.0 * a * c;
+                        if delta < 0.0 {
+                            Err(Mf4Error::conversion(format!("no raw value for physical value {}", phys)))
+                        } else {
+                            Ok((-b + delta.sqrt()) / (2.0 * a))  // take the larger root
+                        }
+                    }
+                },
+                CcType::TableInt((index, value)) => {
+                    // interpolate with keys and values swapped, values must be monotonic
+                    let increasing = value.windows(2).all(|w| w[0] <= w[1]);
+                    let decreasing = value.windows(2).all(|w| w[0] >= w[1]);
+                    if !increasing && !decreasing {
+                        return Err(Mf4Error::conversion("table values are not monotonic, can not be inverted"));
+                    }
+                    let (first, last) = (value[0], value[value.len()-1]);
+                    if (increasing && phys <= first) || (decreasing && phys >= first) {
+                        Ok(index[0])
+                    } else if (increasing && phys >= last) || (decreasing && phys <= last) {
+                        Ok(index[index.len()-1])
+                    } else {
+                        let right_ind = value.iter().position(|v| if increasing { *v > phys } else { *v < phys }).ok_or(Mf4Error::conversion("value out of table range"))?;
+                        let ratio = (index[right_ind] - index[right_ind-1]) / (value[right_ind] - value[right_ind-1]);
+                        Ok(index[right_ind-1] + ratio * (phys - value[right_ind-1]))
+                    }
+                },
+                CcType::Table((index, value)) => {
+                    // raw key with the closest physical value
+                    value.iter().zip(index.iter())
+                        .min_by(|x, y| (x.0 - phys).abs().total_cmp(&(y.0 - phys).abs()))
+                        .map(|(_, k)| *k)
+                        .ok_or(Mf4Error::conversion("empty table conversion"))
+                },
+                CcType::Polynomial(p) => {
+                    let x = (p[1] + phys * p[0]) / (phys * p[2] + p[3]);
+                    Ok(x + p[4] + p[5])
+                },
+                other_type => Err(Mf4Error::conversion(format!("{:?} does not support inverse conversion", other_type)))
+            }
+        }
+
+        pub fn convert_text_to_raw(&self, buf: &mut Cursor<&[u8]>, text: &str) -> Result<f64, Mf4Error> {
+            /* text back to raw value for value to text conversions */
+            if let Some(inverse) = &self.inverse {
+                if let Ok(DataValue::REAL(v)) = inverse.convert_from_text(buf, &vec![text.to_string()]) {
+                    return v.first().copied().ok_or(Mf4Error::conversion("empty inverse conversion result"));
+                }
+            }
+            let matched = |ref_text: &Vec<TextOrScale>| ref_text.iter().position(|t| match t {
+                TextOrScale::Text(t) => t == text,
+                TextOrScale::Scale(_) => false,
+            });
+            match &self.cc_type {
+                CcType::Value2Text((value, ref_text)) => {
+                    match matched(ref_text) {
+                        Some(ind) if ind < value.len() => Ok(value[ind]),
+                        _ => Err(Mf4Error::conversion(format!("text {} not found in conversion", text)))
+                    }
+                },
+                CcType::ValueRange2Text((value, ref_text)) => {
+                    match matched(ref_text) {
+                        Some(ind) if ind*2 < value.len() => Ok(value[ind*2]),   // lower limit of the range
+                        _ => Err(Mf4Error::conversion(format!("text {} not found in conversion", text)))
+                    }
+                },
+                other_type => Err(Mf4Error::conversion(format!("{:?} does not support text to raw conversion", other_type)))
+            }
+        }
+
+        pub fn convert_from_text(&self, buf: &mut Cursor<&[u8]>, inp: &Vec<String>) -> Result<DataValue, Mf4Error> {
             match &self.cc_type {
                 CcType::Text2Value((text, value)) => {
                     let mut ref_text: Vec<String> = Vec::new();
@@ -359,7 +606,7 @@ pub mod conversion {
                 CcType::Text2Text(text) => {
                     let total_num: usize = text.len();
                     if total_num % 2 == 0 {
-                        Err("text2text cc block has odd number of elements".into())
+                        Err(Mf4Error::conversion("text2text cc block has odd number of elements"))
                     } else {
                         let half_num = total_num / 2;
                         let mut ref_text = Vec::new();
@@ -388,7 +635,7 @@ pub mod conversion {
                 },
                 CcType::OneToOne => Ok(DataValue::STRINGS(inp.clone())),
                 other_type => {
-                    Err(format!("{:?} does not support from text conversions", other_type).into())
+                    Err(Mf4Error::conversion(format!("{:?} does not support from text conversions", other_type)))
                 },
             }
         }
@@ -1,9 +1,13 @@
 pub mod channelgroup {
+    use crate::error::Mf4Error;
     use std::io::Cursor;
+    use crate::components::md::metadata::MdComment;
     use crate::block::{BlockInfo, BlockDesc};
-    use crate::parser::{get_clean_text, get_block_desc_by_name, get_child_links};
+    use crate::parser::{get_clean_text, get_clean_text_v3, get_block_desc_by_name, get_block_desc_v3_by_name, get_child_links, get_child_links_v3};
     use crate::components::si::sourceinfo::SourceInfo;
     use crate::components::cn::channel::Channel;
+    use crate::components::sr::samplereduction::SampleReduction;
+    use crate::components::ca::channelarray::ArrayStorage;
 
     #[derive(Debug, Clone)]
     pub struct ChannelGroup {
@@ -20,17 +24,25 @@ pub mod channelgroup {
         cg_flags: u16,
         is_vlsd: bool,
         total_bytes: u64,   // for VLSD cg
+        offset: u64,  // block offset in file
+        remote_master: u64,  // cg_cg_master; offset of the channel group holding the master channel
+        sample_reductions: Vec<SampleReduction>,
+        template_channels: Vec<Channel>,  // CG and DG template arrays; elements are stored outside this channel group
     }
 
     impl ChannelGroup {
         
-        pub fn new(buf:&mut Cursor<&[u8]>, offset: u64) -> Result<Self, Box<dyn std::error::Error>> {
-            let cg_desc: &'static BlockDesc = get_block_desc_by_name("CG".to_string()).unwrap();
-            let info: BlockInfo = cg_desc.try_parse_buf(buf, offset).unwrap();
-            let acq_name: String = get_clean_text(buf, info.get_link_offset_normal("cg_tx_acq_name").unwrap())
+        pub fn new(buf:&mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_inner(buf, offset).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_inner(buf:&mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let cg_desc: &'static BlockDesc = get_block_desc_by_name("CG".to_string()).ok_or("CG block not found")?;
+            let info: BlockInfo = cg_desc.try_parse_buf(buf, offset)?;
+            let acq_name: String = get_clean_text(buf, info.get_link_offset_normal("cg_tx_acq_name").unwrap_or(0))
                                     .unwrap_or("".to_owned());  // Nil is allowed
-            let acq_source: SourceInfo = SourceInfo::new(buf, info.get_link_offset_normal("cg_si_acq_source").unwrap()).unwrap();
-            let comments: String = get_clean_text(buf, info.get_link_offset_normal("cg_md_comment").unwrap())
+            let acq_source: SourceInfo = SourceInfo::new(buf, info.get_link_offset_normal("cg_si_acq_source").unwrap_or(0))?;
+            let comments: String = get_clean_text(buf, info.get_link_offset_normal("cg_md_comment").unwrap_or(0))
                                     .unwrap_or("".to_owned());   // Nil is allowed
             let path_sep: String = match info.get_data_value_first::<u16>("cg_path_separator") {
                 Some(0x2F) => "/".to_owned(),
@@ -46,7 +58,16 @@ pub mod channelgroup {
             let invalid_bytes: u32 = info.get_data_value_first("cg_inval_bytes")                                                                                                                                      
                                     .ok_or("cg_invalid_bytes not found")?;
             let cg_flags: u16 = info.get_data_value_first("cg_flags").ok_or("cg_flags not found")?;
+            let remote_master: u64 = info.get_link_offset_normal("cg_cg_master").unwrap_or(0);  // only since MDF 4.2
+            let mut sample_reductions: Vec<SampleReduction> = Vec::new();
+            let sr_first: u64 = info.get_link_offset_normal("cg_sr_first").unwrap_or(0);
+            if sr_first != 0 {
+                for sr_link in get_child_links(buf, sr_first, "SR")? {
+                    sample_reductions.push(SampleReduction::new(buf, sr_link).map_err(|e| e.with_offset(sr_link))?);
+                }
+            }
             let mut channels: Vec<Channel> = Vec::new();
+            let mut template_channels: Vec<Channel> = Vec::new();
             let mut master: Option<Channel> = None;
             let mut is_vlsd: bool = false;
             let mut total_bytes: u64 = (data_bytes + invalid_bytes) as u64 * cycle_count ;
@@ -54,12 +75,14 @@ pub mod channelgroup {
                 is_vlsd = true;
                 total_bytes = data_bytes as u64 | (invalid_bytes as u64) << 32;
             } else {
-                let cn_link_list: Vec<u64> = get_child_links(buf, info.get_link_offset_normal("cg_cn_first").unwrap(), "CN").unwrap();
+                let cn_link_list: Vec<u64> = get_child_links(buf, info.get_link_offset_normal("cg_cn_first").unwrap_or(0), "CN")?;
                 cn_link_list.into_iter().for_each(|cn_link| {
                 if let Ok(mut cn) = Channel::new(buf, cn_link) {
                     Self::new_channel_name(&mut cn, &acq_name);
                     if cn.is_master() {
                         master = Some(cn);
+                    } else if cn.get_array().is_some_and(|ca| ca.get_storage() != ArrayStorage::CnTemplate) {
+                        template_channels.push(cn);
                     } else if cn.get_array().is_some() {
                         if let Ok(cn_array) = cn.generate_array_element_channel() {
                             channels.extend(cn_array);
@@ -90,7 +113,57 @@ pub mod channelgroup {
                 cg_flags,
                 is_vlsd,
                 total_bytes,
-                
+                offset,
+                remote_master,
+                sample_reductions,
+                template_channels,
+            })
+        }
+
+        pub fn new_v3(buf:&mut Cursor<&[u8]>, offset: u64, big_endian: bool) -> Result<Self, Mf4Error> {
+            Self::new_v3_inner(buf, offset, big_endian).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_v3_inner(buf:&mut Cursor<&[u8]>, offset: u64, big_endian: bool) -> Result<Self, Mf4Error> {
+            let cg_desc: &'static BlockDesc = get_block_desc_v3_by_name("CG".to_string()).ok_or("CG block not found")?;
+            let info: BlockInfo = cg_desc.try_parse_buf_v3(buf, offset)?;
+            let comments: String = get_clean_text_v3(buf, info.get_link_offset_normal("cg_tx_comment").unwrap_or(0))
+                                    .unwrap_or("".to_owned());   // Nil is allowed
+            let record_id: u64 = info.get_data_value_first::<u16>("cg_record_id")
+                                    .ok_or("cg_record_id not found")? as u64;
+            let cycle_count: u64 = info.get_data_value_first::<u32>("cg_cycle_count")
+                                    .ok_or("cg_cycle_count not found")? as u64;
+            let data_bytes: u32 = info.get_data_value_first::<u16>("cg_record_size")
+                                    .ok_or("cg_record_size not found")? as u32;
+            let mut channels: Vec<Channel> = Vec::new();
+            let mut master: Option<Channel> = None;
+            let cn_link_list: Vec<u64> = get_child_links_v3(buf, info.get_link_offset_normal("cg_cn_first").unwrap_or(0), "CN")?;
+            for cn_link in cn_link_list {
+                let cn: Channel = Channel::new_v3(buf, cn_link, big_endian).map_err(|e| e.with_offset(cn_link))?;
+                if cn.is_master() {
+                    master = Some(cn);
+                } else {
+                    channels.push(cn);
+                }
+            }
+            Ok(Self {
+                acq_name: "".to_owned(),
+                acq_source: SourceInfo::default(),
+                comments,
+                path_sep: ".".to_owned(),
+                record_id,
+                cycle_count,
+                data_bytes,
+                invalid_bytes: 0,
+                channels,
+                master,
+                cg_flags: 0,
+                is_vlsd: false,
+                total_bytes: data_bytes as u64 * cycle_count,
+                offset,
+                remote_master: 0,
+                sample_reductions: Vec::new(),
+                template_channels: Vec::new(),
             })
         }
 
@@ -134,6 +207,11 @@ pub mod channelgroup {
             &self.comments
         }
 
+        pub fn get_md_comment(&self) -> MdComment {
+            // comment parsed from MD block xml
+            MdComment::parse(&self.comments)
+        }
+
         pub fn get_path_sep(&self) -> &str {
             &self.path_sep
         }
@@ -173,6 +251,60 @@ pub mod channelgroup {
             self.master.as_ref()
         }
 
+        pub fn get_offset(&self) -> u64 {
+            self.offset
+        }
+
+        pub fn get_remote_master(&self) -> Option<u64> {
+            // master channel is in another channel group
+            if self.master.is_none() && self.remote_master != 0 {
+                Some(self.remote_master)
+            } else {
+                None
+            }
+        }
+
+        pub fn generate_template_groups(&self) -> Result<Vec<(Option<u64>, ChannelGroup)>, Mf4Error> {
+            /* one channel group for each element of CG and DG template arrays, in the order of their linear index;
+               CG template elements are records of this data group with record id (template record id + 1 + linear index),
+               DG template elements have the template layout and come with the data block (ca_data) holding their records */
+            let mut groups: Vec<(Option<u64>, ChannelGroup)> = Vec::new();
+            for cn in self.template_channels.iter() {
+                let ca = match cn.get_array() {
+                    Some(ca) => ca,
+                    None => continue,
+                };
+                let elements: Vec<Channel> = cn.generate_template_element_channels()?;
+                for (element, index) in elements.into_iter().zip(ca.generate_array_indexs()) {
+                    let linear_index: usize = ca.calculate_linear_index(&index)?;
+                    let cycle_count: u64 = *ca.get_cycle_counts().get(linear_index).ok_or("Missing cycle count of array element")?;
+                    let (data_link, record_id) = match ca.get_storage() {
+                        ArrayStorage::CgTemplate => (None, self.record_id + 1 + linear_index as u64),
+                        ArrayStorage::DgTemplate => (Some(*ca.get_data_links().get(linear_index).ok_or("Missing data link of array element")?),
+                                                     self.record_id),
+                        ArrayStorage::CnTemplate => continue,
+                    };
+                    if let Some((_, cg)) = groups.iter_mut().find(|(link, cg)| *link == data_link && cg.record_id == record_id) {
+                        cg.channels.push(element);   // elements of several template arrays share these records
+                        continue;
+                    }
+                    let mut cg: ChannelGroup = self.clone();
+                    cg.channels = vec![element];
+                    cg.template_channels = Vec::new();
+                    cg.sample_reductions = Vec::new();
+                    cg.record_id = record_id;
+                    cg.cycle_count = cycle_count;
+                    cg.total_bytes = (cg.data_bytes + cg.invalid_bytes) as u64 * cg.cycle_count;
+                    groups.push((data_link, cg));
+                }
+            }
+            Ok(groups)
+        }
+
+        pub fn get_sample_reductions(&self) -> &Vec<SampleReduction> {
+            &self.sample_reductions
+        }
+
         pub fn nth_cn(&self, n: usize) -> Option<&Channel> {
             self.channels.get(n)
         }
@@ -0,0 +1,117 @@
+pub mod channelhierarchy {
+    use std::io::Cursor;
+    use std::collections::HashSet;
+    use crate::block::BlockInfo;
+    use crate::parser::{get_clean_text, get_block_desc_by_name, get_child_links};
+    use crate::components::cn::channel::Channel;
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ChType {
+        Group,
+        Function,
+        Structure,
+        MapList,
+        InputVariables,
+        OutputVariables,
+        LocalVariables,
+        CalibrationDefinition,
+        CalibrationReference,
+        Unknown(u8),
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct ChannelHierarchy {
+        name: String,
+        comment: String,
+        ch_type: ChType,
+        elements: Vec<(u64, u64, u64)>,  // (DG, CG, CN) block offsets
+        element_names: Vec<String>,  // channel names of elements
+        children: Vec<ChannelHierarchy>,
+    }
+
+    impl ChannelHierarchy {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_inner(buf, offset, &mut HashSet::new())
+        }
+
+        fn new_inner(buf: &mut Cursor<&[u8]>, offset: u64, visited: &mut HashSet<u64>) -> Result<Self, Mf4Error> {
+            if !visited.insert(offset) {
+                return Err(Mf4Error::corrupt_link(offset));   // cyclic channel hierarchy
+            }
+            let ch_desc = get_block_desc_by_name("CH".to_string()).ok_or("CH block not found")?;
+            let info: BlockInfo = ch_desc.try_parse_buf(buf, offset)?;
+            let name: String = get_clean_text(buf, info.get_link_offset_normal("ch_tx_name").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("ch_md_comment").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let ch_type: ChType = match info.get_data_value_first::<u8>("ch_type").ok_or("ch_type not found")? {
+                0 => ChType::Group,
+                1 => ChType::Function,
+                2 => ChType::Structure,
+                3 => ChType::MapList,
+                4 => ChType::InputVariables,
+                5 => ChType::OutputVariables,
+                6 => ChType::LocalVariables,
+                7 => ChType::CalibrationDefinition,
+                8 => ChType::CalibrationReference,
+                other => ChType::Unknown(other),
+            };
+            let links: Vec<u64> = info.get_link_offset_vec("ch_element").unwrap_or_default();
+            let elements: Vec<(u64, u64, u64)> = links.chunks_exact(3).map(|x| (x[0], x[1], x[2])).collect();
+            let element_names: Vec<String> = elements.iter().map(|(_, _, cn)| {
+                Channel::new(buf, *cn).map(|cn| cn.get_name().to_string()).unwrap_or("".to_string())
+            }).collect();
+            let mut children: Vec<ChannelHierarchy> = Vec::new();
+            let first_child: u64 = info.get_link_offset_normal("ch_ch_first").unwrap_or(0);
+            if first_child != 0 {
+                for link in get_child_links(buf, first_child, "CH")? {
+                    children.push(Self::new_inner(buf, link, visited)?);
+                }
+            }
+            Ok(Self {
+                name,
+                comment,
+                ch_type,
+                elements,
+                element_names,
+                children,
+            })
+        }
+
+        pub fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn get_comment(&self) -> &str {
+            &self.comment
+        }
+
+        pub fn get_type(&self) -> &ChType {
+            &self.ch_type
+        }
+
+        pub fn get_elements(&self) -> &Vec<(u64, u64, u64)> {
+            &self.elements
+        }
+
+        pub fn get_element_names(&self) -> &Vec<String> {
+            &self.element_names
+        }
+
+        pub fn get_children(&self) -> &Vec<ChannelHierarchy> {
+            &self.children
+        }
+
+        pub fn get_channel_paths(&self, sep: &str) -> Vec<String> {
+            /* "parent/child/channel" style path for every channel below this node */
+            let mut paths: Vec<String> = self.element_names.iter()
+                                             .map(|cn| format!("{}{}{}", self.name, sep, cn)).collect();
+            for child in self.children.iter() {
+                paths.extend(child.get_channel_paths(sep).into_iter().map(|p| format!("{}{}{}", self.name, sep, p)));
+            }
+            paths
+        }
+    }
+}
@@ -1,21 +1,22 @@
 pub mod channel {
     use std::io::Cursor;
+    use crate::components::md::metadata::MdComment;
     use std::fmt::Display;
     use half::f16;
     use indexmap::IndexMap;
     use std::borrow::Cow;
     use crate::block::BlockDesc;
     use crate::components::dg::datagroup::DataGroup;
-    use crate::parser::{get_block_desc_by_name, get_child_links, get_clean_text, peek_block_type};
+    use crate::parser::{get_block_desc_by_name, get_block_desc_v3_by_name, get_child_links, get_clean_text, get_clean_text_v3, peek_block_type};
     use crate::components::cc::conversion::Conversion;
     use crate::components::si::sourceinfo::SourceInfo;
     use crate::components::cg::channelgroup::ChannelGroup;
-    use crate::data_serde::{bytes_and_bits, right_shift_bytes, DataValue, FromBeBytes, FromLeBytes, UTF16String};
-    use crate::components::dx::dataxxx::{read_data_block, VirtualBuf};
+    use crate::data_serde::{bytes_and_bits, decode_string, extract_bits, parse_canopen_date, parse_canopen_time, parse_complex, right_shift_bytes, DataValue, FromBeBytes, FromLeBytes};
+    use crate::components::dx::dataxxx::{read_data_block_with_cache, VirtualBuf};
     use crate::components::ca::channelarray::ChannelArray;
     
-    type DynError = Box<dyn std::error::Error>;
-    #[derive(Debug, Clone)]
+    use crate::error::Mf4Error;
+    #[derive(Debug, Clone, PartialEq)]
     pub enum SyncType {
         None,
         Time,
@@ -40,30 +41,35 @@ pub mod channel {
         bytes_num: u32,
         master: bool,
         cn_data: u64,
-        sub_channels: Option<Vec<Channel>>,   // for composed signals; also for MLSD channel
+        sub_channels: Option<Vec<Channel>>,   // for composed signals; also for MLSD and VLSC channel
         array: Option<ChannelArray>,
         cn_flags: u32,
+        inval_bit_pos: u32,
         cn_compositon: u64,
         need_bitwise_operation: bool,
     }
 
     impl Channel {
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError> {
-            let desc: &BlockDesc = get_block_desc_by_name("CN".to_string()).expect("CN block not found");
-            let info: crate::block::BlockInfo = desc.try_parse_buf(buf, offset).unwrap();
-            let name: String = get_clean_text(buf, info.get_link_offset_normal("cn_tx_name").unwrap())
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_inner(buf, offset).map_err(|e| e.with_offset(offset))
+        }
+
+        fn new_inner(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let desc: &BlockDesc = get_block_desc_by_name("CN".to_string()).ok_or("CN block not found")?;
+            let info: crate::block::BlockInfo = desc.try_parse_buf(buf, offset)?;
+            let name: String = get_clean_text(buf, info.get_link_offset_normal("cn_tx_name").unwrap_or(0))
                                .unwrap_or("".to_string());
-            let source: SourceInfo = SourceInfo::new(buf, info.get_link_offset_normal("cn_si_source").unwrap())?;
-            let conversion: Conversion = Conversion::new(buf, info.get_link_offset_normal("cn_cc_conversion").unwrap())?;
-            let unit: String = get_clean_text(buf, info.get_link_offset_normal("cn_md_unit").unwrap())
+            let source: SourceInfo = SourceInfo::new(buf, info.get_link_offset_normal("cn_si_source").unwrap_or(0))?;
+            let conversion: Conversion = Conversion::new(buf, info.get_link_offset_normal("cn_cc_conversion").unwrap_or(0))?;
+            let unit: String = get_clean_text(buf, info.get_link_offset_normal("cn_md_unit").unwrap_or(0))
                                 .unwrap_or("".to_string());
-            let comment: String = get_clean_text(buf, info.get_link_offset_normal("cn_md_comment").unwrap())
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("cn_md_comment").unwrap_or(0))
                                 .unwrap_or("".to_string());
             let cn_type: u8 = info.get_data_value_first("cn_type").ok_or("cn_type not found")?;
             let master: bool = match cn_type {
-                0 | 1 | 5 | 6 => false,  // 5 :: MLSD 
+                0 | 1 | 4 | 5 | 6 | 7 => false,  // 4 :: synchronization; 5 :: MLSD; 7 :: VLSC
                 2 | 3 => true,
-                _ => return Err(format!("cn_type {} not supportted yet.", cn_type).into()),
+                _ => return Err(Mf4Error::unsupported(format!("cn_type {}", cn_type))),
             };
             let sync_type: SyncType = match info.get_data_value_first::<u8>("cn_sync_type") {
                 Some(0) => SyncType::None,
@@ -77,13 +83,15 @@ pub mod channel {
             let bit_offset: u8 = info.get_data_value_first("cn_bit_offset").ok_or("cn_bit_offset not found")?;
             let byte_offset: u32 = info.get_data_value_first("cn_byte_offset").ok_or("cn_byte_offset not found")?;
             let bit_count: u32 = info.get_data_value_first("cn_bit_count").ok_or("cn_bit_count not found")?;
-            let bytes_num: u32 = (bit_count as f32 / 8.0).ceil() as u32;
+            let bytes_num: u32 = (bit_offset as u32 + bit_count).div_ceil(8);   // bytes spanned in the record
             let cn_data: u64 = info.get_link_offset_normal("cn_data").unwrap_or(0);
             let cn_compositon = info.get_link_offset_normal("cn_composition").unwrap_or(0);
             let (mut sub_channels, array) = if let Ok(block_type) = peek_block_type(buf, cn_compositon) {
                 match block_type.as_str() {
                     "CN" => {
-                        if data_type == 10 {
+                        if cn_type == 7 {   // size channel of VLSC channel; it is a channel of the same channel group
+                            (Some(vec![Channel::new(buf, cn_compositon)?]), None)
+                        } else if data_type == 10 {
                             let mut channels: Vec<Channel> = Vec::new();
                             let links: Vec<u64> = get_child_links(buf, cn_compositon, "CN")?;
                             links.iter().for_each(|l: &u64| {
@@ -105,6 +113,7 @@ pub mod channel {
                 }
             } else { (None, None) };
             let cn_flags: u32 = info.get_data_value_first::<u32>("cn_flags").ok_or("CN flags not found")?;
+            let inval_bit_pos: u32 = info.get_data_value_first::<u32>("cn_inval_bit_pos").ok_or("cn_inval_bit_pos not found")?;
             if cn_type == 5u8 {
                 if cn_data != 0x00u64 {
                     if let Ok(ch) = Channel::new(buf, cn_data) {
@@ -116,7 +125,11 @@ pub mod channel {
                     return Err("CN data should not be empty for MLSD channel.".into())
                 }
             }
-            let need_bitwise_operation = bit_offset != 0u8 || bit_count != bytes_num * 8u32;
+            if cn_type == 7 && sub_channels.as_ref().and_then(|s| s.first()).is_none_or(|s| s.data_type > 1) {
+                return Err("VLSC channel needs an unsigned integer size channel".into())
+            }
+            let need_bitwise_operation = bit_offset != 0u8 || bit_count != bytes_num * 8u32
+                                         || (data_type <= 3 && !bytes_num.is_power_of_two());
             Ok(Self {
                 name,
                 source,
@@ -135,11 +148,76 @@ pub mod channel {
                 sub_channels,
                 array,
                 cn_flags,
+                inval_bit_pos,
                 cn_compositon,
                 need_bitwise_operation,
             })
         }
 
+        pub fn new_v3(buf: &mut Cursor<&[u8]>, offset: u64, big_endian: bool) -> Result<Self, Mf4Error> {
+            /* MDF 3.x channel; values are mapped to MDF 4.x channel types and data types */
+            let desc: &BlockDesc = get_block_desc_v3_by_name("CN".to_string()).ok_or("CN block not found")?;
+            let info: crate::block::BlockInfo = desc.try_parse_buf_v3(buf, offset)?;
+            let short_name: String = info.get_data_value_copy("cn_short_name").ok_or("cn_short_name not found")?.try_into()?;
+            let name: String = info.get_data_value_first::<u32>("cn_tx_long_name")
+                                   .and_then(|link| get_clean_text_v3(buf, link as u64).ok())
+                                   .filter(|s| !s.is_empty())
+                                   .unwrap_or(short_name.trim_end_matches('\0').to_string());
+            let source: SourceInfo = SourceInfo::new_v3(buf, info.get_link_offset_normal("cn_ce_source").unwrap_or(0))?;
+            let conversion: Conversion = Conversion::new_v3(buf, info.get_link_offset_normal("cn_cc_conversion").unwrap_or(0))?;
+            let unit: String = conversion.get_unit().to_string();
+            let description: String = info.get_data_value_copy("cn_description").ok_or("cn_description not found")?.try_into()?;
+            let comment: String = get_clean_text_v3(buf, info.get_link_offset_normal("cn_tx_comment").unwrap_or(0))
+                                   .unwrap_or(description.trim_end_matches('\0').to_string());
+            let (cn_type, master, sync_type) = match info.get_data_value_first::<u16>("cn_type") {
+                Some(0) => (0u8, false, SyncType::None),
+                Some(1) => (2u8, true, SyncType::Time),
+                _ => return Err(Mf4Error::unsupported("cn_type of MDF 3.x")),
+            };
+            let v3_data_type: u16 = info.get_data_value_first("cn_data_type").ok_or("cn_data_type not found")?;
+            let data_type: u8 = match (v3_data_type, big_endian) {
+                (0, false) | (13, _) => 0,   // unsigned integer
+                (0, true) | (9, _) => 1,
+                (1, false) | (14, _) => 2,   // signed integer
+                (1, true) | (10, _) => 3,
+                (2, false) | (3, false) | (15, _) | (16, _) => 4,   // float and double
+                (2, true) | (3, true) | (11, _) | (12, _) => 5,
+                (7, _) => 6,   // string; ISO-8859-1 encoded
+                (8, _) => 10,  // byte array
+                (num, _) => return Err(Mf4Error::unsupported(format!("cn_data_type {} of MDF 3.x", num))),  // VAX floats
+            };
+            let start_offset: u16 = info.get_data_value_first("cn_start_offset").ok_or("cn_start_offset not found")?;
+            let add_byte_offset: u16 = info.get_data_value_first("cn_add_byte_offset").unwrap_or(0);
+            let bit_offset: u8 = (start_offset % 8) as u8;
+            let byte_offset: u32 = (start_offset / 8) as u32 + add_byte_offset as u32;
+            let bit_count: u32 = info.get_data_value_first::<u16>("cn_bit_count").ok_or("cn_bit_count not found")? as u32;
+            let bytes_num: u32 = (bit_offset as u32 + bit_count).div_ceil(8);   // bytes spanned in the record
+            let need_bitwise_operation = bit_offset != 0u8 || bit_count != bytes_num * 8u32
+                                         || (data_type <= 3 && !bytes_num.is_power_of_two());
+            Ok(Self {
+                name,
+                source,
+                conversion,
+                unit,
+                comment,
+                cn_type,
+                sync_type,
+                data_type,
+                bit_offset,
+                byte_offset,
+                bit_count,
+                master,
+                bytes_num,
+                cn_data: 0,
+                sub_channels: None,
+                array: None,
+                cn_flags: 0,
+                inval_bit_pos: 0,
+                cn_compositon: 0,
+                need_bitwise_operation,
+            })
+        }
+
         pub fn get_name(&self) -> &str {
             &self.name
         }
@@ -151,11 +229,7 @@ pub mod channel {
         fn is_cg_vlsd_channel(&self, buf: &mut Cursor<&[u8]>) -> bool {
             if self.cn_type == 1u8 {
                 if self.cn_data != 0x00u64 {
-                    let block_type: String = peek_block_type(buf, self.cn_data).unwrap();
-                    match block_type.as_str() {
-                        "CG" => true,
-                        _ => false
-                    }
+                    matches!(peek_block_type(buf, self.cn_data).as_deref(), Ok("CG"))
                 } else { false}
             } else { false }
         }
@@ -181,6 +255,18 @@ pub mod channel {
             (self.cn_flags & (0x01<<10)) != 0
         }
 
+        pub fn is_all_invalid(&self) -> bool {
+            (self.cn_flags & 0x01) != 0
+        }
+
+        pub fn has_inval_bit(&self) -> bool {
+            (self.cn_flags & (0x01<<1)) != 0
+        }
+
+        pub fn get_inval_bit_pos(&self) -> u32 {
+            self.inval_bit_pos
+        }
+
         pub fn get_sub_channels(&self) -> Option<&Vec<Channel>> {
             self.sub_channels.as_ref()
         }
@@ -209,6 +295,11 @@ pub mod channel {
             &self.comment
         }
 
+        pub fn get_md_comment(&self) -> MdComment {
+            // comment parsed from MD block xml
+            MdComment::parse(&self.comment)
+        }
+
         pub fn get_bit_size(&self) -> u32 {
             self.bit_count
         }
@@ -221,24 +312,33 @@ pub mod channel {
             self.bit_offset
         }
 
+        pub fn is_sync_channel(&self) -> bool {
+            self.cn_type == 4
+        }
+
+        pub fn get_sync_attachment(&self) -> Option<u64> {
+            /* offset of the AT block with the synchronized stream, e.g. a video file */
+            if self.is_sync_channel() && self.cn_data != 0 { Some(self.cn_data) } else { None }
+        }
+
         pub fn is_master(&self) -> bool {
             self.master
         }
 
         pub fn is_composition(&self) -> bool {
-            self.data_type == 10 && self.sub_channels.is_some() && self.cn_compositon != 0
+            self.data_type == 10 && self.sub_channels.is_some() && self.cn_compositon != 0 && self.cn_type != 7
         }
 
-        pub fn get_data_raw(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<DataValue, DynError> {
+        pub fn get_data_raw(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<DataValue, Mf4Error> {
             let bits: u32 = self.get_bit_size();
-            if self.get_cn_type() == &1 { 
-                // special case for VLSD ; record bytes are only offset of SD blocks ; this is the same both for SD/CG type VLSD channels
+            if self.get_cn_type() == &1 || self.get_cn_type() == &7 { 
+                // special case for VLSD/VLSC ; record bytes are only offset of SD blocks ; this is the same both for SD/CG type VLSD channels
                 return Ok(DataValue::UINT64(self.gen_value_vec::<u64>(file, dg, cg)?))
             } else if self.get_cn_type() == &6 || self.get_cn_type() == &3{ // virtual data channel
                 if self.get_data_type() == 0 {
                     return Ok(DataValue::UINT64((0..cg.get_cycle_count()).collect()))
                 } else {
-                    return Err("Virtual data channel only support little-edian unsigned interger data type".into())
+                    return Err(Mf4Error::unsupported(format!("virtual data channel of data type {}", self.get_data_type())))
                 }
             }
             match self.get_data_type() {
@@ -279,18 +379,30 @@ pub mod channel {
                         Err("Invalid bit size.".into())
                     }
                 },
-                6 | 7 => {
-                    Ok(DataValue::STRINGS(self.gen_value_vec::<String>(file, dg, cg)?))
+                6..=9 => {
+                    let bytes: Vec<Vec<u8>> = self.gen_value_vec::<Vec<u8>>(file, dg, cg)?;
+                    Ok(DataValue::STRINGS(bytes.iter().map(|b| decode_string(b, self.get_data_type())).collect()))
+                },
+                10 => Ok(DataValue::BYTEARRAY(self.get_byte_array(file, dg, cg)?)),
+                11 | 12 => Ok(self.typed_bytes(self.get_byte_array(file, dg, cg)?)),
+                13 => {
+                    let bytes: Vec<Vec<u8>> = self.get_byte_array(file, dg, cg)?;
+                    Ok(DataValue::DATETIME(bytes.iter().map(|b| parse_canopen_date(b)).collect::<Result<_, _>>()?))
+                },
+                14 => {
+                    let bytes: Vec<Vec<u8>> = self.get_byte_array(file, dg, cg)?;
+                    Ok(DataValue::DATETIME(bytes.iter().map(|b| parse_canopen_time(b)).collect::<Result<_, _>>()?))
                 },
-                8 | 9 => {
-                    let s: Vec<UTF16String> = self.gen_value_vec::<UTF16String>(file, dg, cg)?;
-                    Ok(DataValue::STRINGS(s.into_iter().map(|s| s.inner).collect()))
+                15 | 16 => {
+                    let bytes: Vec<Vec<u8>> = self.get_byte_array(file, dg, cg)?;
+                    let big_endian: bool = self.get_data_type() == 16;
+                    Ok(DataValue::COMPLEX(bytes.iter().map(|b| parse_complex(b, big_endian)).collect::<Result<_, _>>()?))
                 },
                 _ => Err("Invalid data type.".into())
             }
         }
 
-        fn get_byte_array(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<Vec<Vec<u8>>, DynError> {
+        fn get_byte_array(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<Vec<Vec<u8>>, Mf4Error> {
             let mut bytes_array_vec: Vec<Vec<u8>> = Vec::new();
             for i in 0..cg.get_cycle_count() {
                 let rec_data: Vec<u8> = dg.get_cg_data(cg.get_record_id(), i, file)
@@ -307,7 +419,7 @@ pub mod channel {
             Ok(bytes_array_vec)
         }
 
-        pub fn get_data(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<DataValue, DynError> {
+        pub fn get_data(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<DataValue, Mf4Error> {
             /* handle some exceptions first */
             if self.is_composition() {   // for compact structure
                 let mut value_map: IndexMap<String, DataValue> = IndexMap::new();
@@ -319,8 +431,8 @@ pub mod channel {
                       }).unwrap_or(());
                 }
                 return Ok(DataValue::STRUCT(value_map))
-            } else if self.data_type == 10 && self.get_array().is_none() { // for pure BYTEARRAY
-                return Ok(DataValue::BYTEARRAY(self.get_byte_array(file, dg, cg)?))
+            } else if matches!(self.data_type, 10..=12) && self.get_array().is_none() && !matches!(self.cn_type, 1 | 7) { // for pure BYTEARRAY and MIME samples
+                return Ok(self.typed_bytes(self.get_byte_array(file, dg, cg)?))
             }
 
             /* generic process */
@@ -328,16 +440,29 @@ pub mod channel {
             if let Some(id) = self.get_cg_rec_id(file) {
                 let offsets: Vec<u64> = data_raw.try_into()?;
                 return self.parse_cg_vlsd(file, &offsets, id, dg)
-            } else if self.get_cn_type() == &1 {                                 // for VLSD with SD blocks; not suitable for VLSD with channel groups
+            } else if self.get_cn_type() == &1 {                     // for VLSD with SD blocks; not suitable for VLSD with channel groups
                 let offsets: Vec<u64> = data_raw.try_into()?;
-                return self.parse_sd_data(file, &offsets)
+                return self.parse_sd_data(file, &offsets, dg)
+            } else if self.get_cn_type() == &7 {
+                let offsets: Vec<u64> = data_raw.try_into()?;
+                return self.parse_vlsc_data(file, &offsets, dg, cg)
             }
             if data_raw.is_num() {
                 let float_data: Vec<f64> = data_raw.try_into()?;
                 if self.get_conversion().get_cc_type().is_num() { // conversion target type is numerical
-                    Ok(DataValue::REAL(float_data.into_iter().map(|f| self.get_conversion().convert_num_value(f)).collect()))
+                    Ok(DataValue::REAL(float_data.into_iter().map(|f| self.get_conversion().try_convert_num_value(f)).collect::<Result<_, _>>()?))
+                } else {
+                    Ok(DataValue::MIXED(float_data.into_iter().map(|f| self.get_conversion().convert_to_mix(file, f)).collect::<Result<_, _>>()?))
+                }
+            } else if let DataValue::COMPLEX(values) = data_raw {
+                // conversion is applied to real and imaginary part separately
+                let cc: &Conversion = self.get_conversion();
+                if cc.get_cc_type().is_num() {
+                    Ok(DataValue::COMPLEX(values.into_iter()
+                        .map(|(re, im)| Ok((cc.try_convert_num_value(re)?, cc.try_convert_num_value(im)?)))
+                        .collect::<Result<_, Mf4Error>>()?))
                 } else {
-                    Ok(DataValue::MIXED(float_data.into_iter().map(|f| self.get_conversion().convert_to_mix(file, f).unwrap()).collect()))  // todo: remove unwrap handle errors
+                    Err(Mf4Error::conversion(format!("complex channel {} has a non-numerical conversion", self.get_name())))
                 }
             } else if data_raw.is_strings(){
                 let mut strings: Vec<String> = data_raw.try_into()?;
@@ -354,21 +479,48 @@ pub mod channel {
             }
         }
 
-        fn gen_value_vec<T>(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<Vec<T>, DynError> 
+        pub fn get_validity(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<Vec<bool>, Mf4Error> {
+            /* one flag per sample; false if the sample is marked invalid by the invalidation bit */
+            let sample_num = cg.get_cycle_count();
+            if self.is_all_invalid() {
+                Ok(vec![false; sample_num as usize])
+            } else if !self.has_inval_bit() || cg.get_invalid_bytes() == 0 {
+                Ok(vec![true; sample_num as usize])
+            } else {
+                let mut validity: Vec<bool> = Vec::with_capacity(sample_num as usize);
+                for i in 0..sample_num {
+                    validity.push(!dg.get_inval_bit(cg, i, file, self.inval_bit_pos)?);
+                }
+                Ok(validity)
+            }
+        }
+
+        fn gen_value_vec<T>(&self, file: &mut Cursor<&[u8]>, dg: &DataGroup, cg: &ChannelGroup) -> Result<Vec<T>, Mf4Error> 
         where T: FromBeBytes + FromLeBytes {  /* function used to read record bytes into channel value*/
             let sample_num = cg.get_cycle_count();
             let mut values: Vec<T> = Vec::with_capacity(sample_num as usize);
-            for i in 0..sample_num {
-                let rec_data = dg.get_cn_bytes(cg.get_record_id(), i, file, self)?;
-                values.push(self.convert_to::<T>(rec_data)?);
+            let rec_len = cg.get_data_bytes() as usize;
+            if dg.is_column_oriented() && rec_len > 0 && self.byte_offset as usize + self.get_bytes_num() as usize > rec_len {
+                // values of all records are stored contiguously; a channel must not run into the next record
+                return Err(Mf4Error::Malformed {
+                    reason: format!("channel {} exceeds the record length {} of its column", self.name, rec_len),
+                    offset: Some(cg.get_offset()),
+                })
             }
+            dg.for_each_cn_bytes(file, cg, self, &mut |bytes| {
+                values.push(self.convert_to::<T>(Cow::Borrowed(bytes))?);
+                Ok(())
+            })?;
             Ok(values)
         }
 
-        pub fn convert_to<T>(&self, rec_bytes: Cow<'_, [u8]>) -> Result<T, DynError> 
+        pub fn convert_to<T>(&self, rec_bytes: Cow<'_, [u8]>) -> Result<T, Mf4Error> 
         where T: FromBeBytes + FromLeBytes
         {
-            let data: Cow<'_, [u8]> = if self.need_bitwise_operation {
+            let data: Cow<'_, [u8]> = if self.need_bitwise_operation && self.data_type <= 3 && !matches!(self.cn_type, 1 | 7) {
+                // integer bit fields, also in Motorola byte order
+                Cow::Owned(extract_bits(&rec_bytes, self.bit_offset, self.bit_count, self.data_type % 2 == 1, self.data_type >= 2)?)
+            } else if self.need_bitwise_operation {
                 let mut raw_data = rec_bytes.into_owned();  // has to copy data, cannot change any data in mf4 file mmap
                 if self.bit_offset != 0 {
                     raw_data = right_shift_bytes(&raw_data, self.bit_offset)?
@@ -379,85 +531,143 @@ pub mod channel {
                 rec_bytes   //   to avoid copying any data
             };
             
+            if matches!(self.cn_type, 1 | 7) {
+                return Ok(T::from_le_bytes(&data))    // VLSD/VLSC offsets are always Little Endian regardless of data type
+            }
             match self.data_type {
                 0|2|4|6|7|8 => Ok(T::from_le_bytes(&data)),    // Little Endian
                 1|3|5|9 => Ok(T::from_be_bytes(&data)),        // Big Endian
-                _ => Err("data type not supportted.".into()),
+                _ => Err(Mf4Error::unsupported(format!("data type {}", self.data_type))),
             }
         }
 
+        pub fn get_physical_value(&self, record: &[u8]) -> Result<f64, Mf4Error> {
+            /* numerical value of this channel in one record (without record id) after conversion */
+            let start = self.byte_offset as usize;
+            let end = start + self.get_bytes_num() as usize;
+            let bytes: Cow<'_, [u8]> = Cow::Borrowed(record.get(start..end).ok_or("Record too short for channel")?);
+            let bits: u32 = self.get_bit_size();
+            let raw: f64 = match (self.data_type, bits) {
+                (0 | 1, 0..=8) => self.convert_to::<u8>(bytes)? as f64,
+                (0 | 1, 9..=16) => self.convert_to::<u16>(bytes)? as f64,
+                (0 | 1, 17..=32) => self.convert_to::<u32>(bytes)? as f64,
+                (0 | 1, 33..=64) => self.convert_to::<u64>(bytes)? as f64,
+                (2 | 3, 0..=8) => self.convert_to::<i8>(bytes)? as f64,
+                (2 | 3, 9..=16) => self.convert_to::<i16>(bytes)? as f64,
+                (2 | 3, 17..=32) => self.convert_to::<i32>(bytes)? as f64,
+                (2 | 3, 33..=64) => self.convert_to::<i64>(bytes)? as f64,
+                (4 | 5, 16) => self.convert_to::<f16>(bytes)?.to_f64(),
+                (4 | 5, 32) => self.convert_to::<f32>(bytes)? as f64,
+                (4 | 5, 64) => self.convert_to::<f64>(bytes)?,
+                (data_type, bits) => return Err(Mf4Error::conversion(format!("no physical value for data type {} with {} bits", data_type, bits))),
+            };
+            if self.get_conversion().get_cc_type().is_num() {
+                self.get_conversion().try_convert_num_value(raw)
+            } else {
+                Err(Mf4Error::conversion("conversion of channel is not numerical"))
+            }
+        }
+
+        fn typed_bytes(&self, payloads: Vec<Vec<u8>>) -> DataValue {
+            // MIME samples and streams keep their content type; other byte arrays are returned as is
+            match self.get_mime_type() {
+                Some(mime_type) => DataValue::MIME(mime_type, payloads),
+                None => DataValue::BYTEARRAY(payloads),
+            }
+        }
+
+        pub fn get_mime_type(&self) -> Option<String> {
+            /* content type of MIME sample/stream channels; taken from the unit, or the comment 
+               if there is no unit. Falls back to application/octet-stream */
+            if !matches!(self.data_type, 11 | 12) {
+                return None
+            }
+            let candidates: [String; 2] = [self.unit.trim().to_string(), self.get_md_comment().get_text().trim().to_string()];
+            Some(candidates.into_iter()
+                           .find(|c| c.contains('/') && !c.contains(char::is_whitespace))
+                           .unwrap_or("application/octet-stream".to_string()))
+        }
+
         pub fn get_bytes_num(&self) -> u32 {
             self.bytes_num
         }
 
-        fn parse_sd_data(&self, file: &mut Cursor<&[u8]>, offsets: &Vec<u64>) -> Result<DataValue, DynError> {
-            /* for non-cg vlsd channel ; only support string and raw bytes for now*/
-            let data_blocks: Box<dyn VirtualBuf> = read_data_block(file, self.cn_data)?;
-            let mut sd_data: Vec<String> = Vec::new();  // todo: is there any other possible data types?
-            let mut raw = Vec::new();
+        fn parse_sd_data(&self, file: &mut Cursor<&[u8]>, offsets: &Vec<u64>, dg: &DataGroup) -> Result<DataValue, Mf4Error> {
+            /* for non-cg vlsd channels; every value is prefixed by its length. only support string and raw bytes for now*/
+            let data_blocks: Box<dyn VirtualBuf> = read_data_block_with_cache(file, self.cn_data, dg.get_dz_cache())?;
+            let mut values: Vec<Vec<u8>> = Vec::new();
             for offset in offsets.iter() {
                 let mut four_bytes: [u8; 4] = [0u8; 4];
                 data_blocks.read_virtual_buf(file, *offset, &mut four_bytes)?;
                 let length: u32 = u32::from_le_bytes(four_bytes);
                 let mut data_bytes: Vec<u8> = vec![0u8; length as usize];
                 data_blocks.read_virtual_buf(file, *offset + 4, &mut data_bytes)?;
+                values.push(data_bytes);
+            }
+            self.decode_signal_values(values)
+        }
+
+        fn parse_vlsc_data(&self, file: &mut Cursor<&[u8]>, offsets: &[u64], dg: &DataGroup, cg: &ChannelGroup) -> Result<DataValue, Mf4Error> {
+            /* for vlsc channels; values have no length prefix, the length of each value is read from the size channel */
+            let size_channel: &Channel = self.sub_channels.as_ref().and_then(|s| s.first()).ok_or("VLSC channel without size channel")?;
+            let lengths: Vec<f64> = size_channel.get_data_raw(file, dg, cg)?.try_into()?;
+            let data_blocks: Box<dyn VirtualBuf> = read_data_block_with_cache(file, self.cn_data, dg.get_dz_cache())?;
+            let mut values: Vec<Vec<u8>> = Vec::new();
+            for (offset, length) in offsets.iter().zip(lengths.iter()) {
+                if offset.checked_add(*length as u64).is_none_or(|end| end > data_blocks.get_data_len()) {
+                    return Err("VLSC value exceeds the signal data".into());
+                }
+                let mut data_bytes: Vec<u8> = vec![0u8; *length as usize];
+                data_blocks.read_virtual_buf(file, *offset, &mut data_bytes)?;
+                values.push(data_bytes);
+            }
+            self.decode_signal_values(values)
+        }
+
+        fn decode_signal_values(&self, values: Vec<Vec<u8>>) -> Result<DataValue, Mf4Error> {
+            /* decode variable length values according to the channel data type */
+            let mut sd_data: Vec<String> = Vec::new();  // todo: is there any other possible data types?
+            let mut raw = Vec::new();
+            for data_bytes in values.into_iter() {
                 match self.get_data_type() {
-                    6 | 7 => {
-                        let raw: String = String::from_utf8(data_bytes)?;
-                        sd_data.push(raw.trim_end_matches('\0').to_string());
-                    },
-                    8 => {
-                        let u16str: UTF16String = UTF16String::from_le_bytes(&data_bytes);
-                        sd_data.push(u16str.inner.trim_end_matches('\0').to_string());
-                    },
-                    9 => {
-                        let u16str: UTF16String = UTF16String::from_be_bytes(&data_bytes);
-                        sd_data.push(u16str.inner.trim_end_matches('\0').to_string());
+                    6..=9 => {
+                        sd_data.push(decode_string(&data_bytes, self.get_data_type()));
                     },
-                    10 => {
+                    10..=12 => {
                         raw.push(data_bytes);
                     }
                     num => {
-                        return Err(format!("Can not parse sd data with this type {}", num).into())
+                        return Err(Mf4Error::unsupported(format!("signal data of data type {}", num)))
                     }
                 }
             }
-            if self.get_data_type() == 10 {
-                Ok(DataValue::BYTEARRAY(raw))
+            if matches!(self.get_data_type(), 10..=12) {
+                Ok(self.typed_bytes(raw))
             } else {
                 Ok(DataValue::STRINGS(sd_data))
             }
         }
 
-        fn parse_cg_vlsd(&self, file: &mut Cursor<&[u8]>, offsets: &Vec<u64>, id: u64, dg: &DataGroup) -> Result<DataValue, DynError>{
+        fn parse_cg_vlsd(&self, file: &mut Cursor<&[u8]>, offsets: &Vec<u64>, id: u64, dg: &DataGroup) -> Result<DataValue, Mf4Error>{
             /* for cg vlsd channel ; only support string and raw bytes for now*/
             let mut res: Vec<String> = Vec::new();
             let mut byte_array: Vec<Vec<u8>> = Vec::new();
             for i in 0..offsets.len() { // should be replaced by cg.cycle_count? 
                 let rec_data: Vec<u8> = dg.get_vlsd_cg_data(id, i as u64, file).ok_or("error during reading vlsd records")?;
                 match self.get_data_type() {
-                    6 | 7 => {
-                        let raw: String = String::from_utf8(rec_data)?;
-                        res.push(raw.trim_end_matches('\0').to_string());
-                    },
-                    8 => {
-                        let u16str: UTF16String = UTF16String::from_le_bytes(&rec_data);
-                        res.push(u16str.inner.trim_end_matches('\0').to_string());
-                    },
-                    9 => {
-                        let u16str: UTF16String = UTF16String::from_be_bytes(&rec_data);
-                        res.push(u16str.inner.trim_end_matches('\0').to_string());
+                    6..=9 => {
+                        res.push(decode_string(&rec_data, self.get_data_type()));
                     },
-                    10 => {
+                    10..=12 => {
                         byte_array.push(rec_data);
                     }
                     num => {
-                        return Err(format!("Can not parse sd data with this type {}", num).into())
+                        return Err(Mf4Error::unsupported(format!("signal data of data type {}", num)))
                     }
                 }
             }
-            if self.get_data_type() == 10 {
-                Ok(DataValue::BYTEARRAY(byte_array))
+            if matches!(self.get_data_type(), 10..=12) {
+                Ok(self.typed_bytes(byte_array))
             } else {
                 Ok(DataValue::STRINGS(res))
             }
@@ -475,10 +685,14 @@ pub mod channel {
             self.source = source;
         }
 
-        pub fn generate_array_element_channel(self) -> Result<Vec<Self>, DynError> {
+        pub fn change_inval_bit_pos(&mut self, pos: u32) {
+            self.inval_bit_pos = pos;
+        }
+
+        pub fn generate_array_element_channel(self) -> Result<Vec<Self>, Mf4Error> {
             // this function will consume self
             if self.get_array().is_none() {
-                Err("Not an array element channel.".into())
+                Err(Mf4Error::unsupported(format!("array elements of channel {} without channel array", self.get_name())))
             } else {
                 let mut channels: Vec<Self> = Vec::new();  // element 0
                 let ca: &ChannelArray = self.get_array().unwrap();
@@ -487,8 +701,10 @@ pub mod channel {
                 let si = self.get_source();
                 for (index, name) in indexes.iter().zip(names.iter()) {
                     let new_bytes_offset: u32 = ca.calculate_byte_offset(index)?;
+                    let new_inval_bit_pos: u32 = self.inval_bit_pos + ca.calculate_inval_bit_pos(index)?;
                     let mut new_channel: Channel = self.clone();
                     new_channel.change_byte_offset(new_bytes_offset);
+                    new_channel.change_inval_bit_pos(new_inval_bit_pos);
                     new_channel.set_name(name.to_string());
                     new_channel.change_source(si.clone());
                     channels.push(new_channel);
@@ -497,10 +713,23 @@ pub mod channel {
             }
         }
 
-        pub fn generate_composed_channels(&self) -> Result<Vec<Self>, DynError> {
+        pub fn generate_template_element_channels(&self) -> Result<Vec<Self>, Mf4Error> {
+            /* elements of CG or DG template arrays; each element has the record layout of the template channel */
+            let ca: &ChannelArray = self.get_array()
+                .ok_or_else(|| Mf4Error::unsupported(format!("array elements of channel {} without channel array", self.get_name())))?;
+            let names: Vec<String> = ca.generate_array_names(self.get_name());
+            Ok(names.into_iter().map(|name| {
+                let mut new_channel: Channel = self.clone();
+                new_channel.array = None;   // element channel is a scalar channel in its own record
+                new_channel.set_name(name);
+                new_channel
+            }).collect())
+        }
+
+        pub fn generate_composed_channels(&self) -> Result<Vec<Self>, Mf4Error> {
             /* recursive find */
             if !self.is_composition() {
-                Err("Not a composition channel.".into())
+                Err(Mf4Error::unsupported(format!("composed channels of channel {} without composition", self.get_name())))
             } else  {
                 let mut channels: Vec<Self> = Vec::new();
                 let name: &str = self.get_name();
@@ -3,15 +3,16 @@
 pub mod datagroup {
     use crate::components::cg::channelgroup::ChannelGroup;
     use crate::components::cn::channel::Channel;
-    use crate::parser::{get_block_desc_by_name, get_clean_text, get_child_links};
-    use crate::components::dx::dataxxx::{VirtualBuf, read_data_block};
+    use crate::parser::{get_block_desc_by_name, get_block_desc_v3_by_name, get_clean_text, get_child_links, get_child_links_v3};
+    use crate::components::dx::dataxxx::{VirtualBuf, read_data_block_with_cache, read_column_blocks, is_column_oriented, new_dz_cache, DzCache, DataAndInval, DT, DEFAULT_DZ_CACHE_SIZE};
     use crate::data_serde::DataValue;
     use std::collections::{HashMap, HashSet};
     use std::io::Cursor;
     use std::fmt::Display;
     use std::borrow::Cow;
+    use std::sync::OnceLock;
 
-    type DynError = Box<dyn std::error::Error>;
+    use crate::error::Mf4Error;
     #[derive(Debug, Clone, Copy)]
     pub enum RecIDSize {
         NORECID,
@@ -37,7 +38,7 @@ pub mod datagroup {
             &self.2
         }
 
-        pub fn get_master_channel_data(&self, file: &mut Cursor<&[u8]>) -> Result<DataValue, DynError> {
+        pub fn get_master_channel_data(&self, file: &mut Cursor<&[u8]>) -> Result<DataValue, Mf4Error> {
             if self.get_channel().is_master() {
                 // not possible if the channel link is build from current API
                 Err("Master channel cannot have data.".into())
@@ -45,7 +46,7 @@ pub mod datagroup {
                 let cg: &ChannelGroup = self.get_channel_group();
                 let dg: &DataGroup = self.get_data_group();
                 let master_cn: &Channel = cg.get_master()
-                                            .ok_or::<DynError>("Cannot find master channel".into())?;
+                                            .ok_or::<Mf4Error>("Cannot find master channel".into())?;
                 Ok(master_cn.get_data(file, dg, cg)?)
             }
         }
@@ -59,42 +60,98 @@ pub mod datagroup {
         channel_groups: Vec<ChannelGroup>,
         sorted: bool,
         rec_id_map: HashMap<u64, (u32, u64)>, // record id -> (data bytes, cycle count)
-        offsets_map: HashMap<u64, Vec<u64>>, // record id -> offset
+        offsets_map: OnceLock<HashMap<u64, Vec<u64>>>, // record id -> offset; unsorted data groups are indexed on first data access
         data_block: Box<dyn VirtualBuf>,  // one datagroup have one data_block
+        column_oriented: bool,  // MDF 4.2 column storage; values and invalidation bits are stored separately
+        inval_block: Option<Box<dyn VirtualBuf>>,  // DI blocks of column oriented data group
+        dz_cache: DzCache,  // decompressed DZ blocks; shared by all data groups of one file
+        offset: u64,  // block offset in file
+        element_groups: Vec<DataGroup>,  // records of DG template array elements; each element has its own data block
     }
 
     unsafe impl Send for DataGroup {}
 
-    fn read_rec_id(rec_id_size: RecIDSize, buf: &Box<dyn VirtualBuf>, from:&mut Cursor<&[u8]>, v_offset: u64) -> Result<(u64, u8), DynError> {
+    fn read_rec_id(rec_id_size: RecIDSize, buf: &dyn VirtualBuf, from:&mut Cursor<&[u8]>, v_offset: u64) -> Result<(u64, u8), Mf4Error> {
         // read record id to process ; Note this function will move buf's cursor
         // u64: record id u8: bytes read
         match rec_id_size {
             RecIDSize::NORECID => Ok((0, 0u8)), // do nothing
             RecIDSize::UINT8 => {
                 let mut temp_buf = [0u8; 1];
-                buf.read_virtual_buf(from, v_offset,&mut temp_buf).unwrap();
+                buf.read_virtual_buf(from, v_offset,&mut temp_buf)?;
                 Ok((temp_buf[0] as u64, 1u8))},
             RecIDSize::UINT16 => {
                 let mut temp_buf = [0u8; 2];
-                buf.read_virtual_buf(from, v_offset,&mut temp_buf).unwrap();
+                buf.read_virtual_buf(from, v_offset,&mut temp_buf)?;
                 Ok((u16::from_le_bytes(temp_buf) as u64, 2u8))
             },
             RecIDSize::UINT32 => {
                 let mut temp_buf = [0u8; 4];
-                buf.read_virtual_buf(from, v_offset,&mut temp_buf).unwrap();
+                buf.read_virtual_buf(from, v_offset,&mut temp_buf)?;
                 Ok((u32::from_le_bytes(temp_buf) as u64, 4u8))
             },
             RecIDSize::UINT64 => {
                 let mut temp_buf = [0u8; 8];
-                buf.read_virtual_buf(from, v_offset,&mut temp_buf).unwrap();
+                buf.read_virtual_buf(from, v_offset,&mut temp_buf)?;
                 Ok((u64::from_le_bytes(temp_buf), 8u8))
             }
         }
     }
 
+    fn index_records(buf: &mut Cursor<&[u8]>, rec_id_size: RecIDSize, data_block: &dyn VirtualBuf,
+                     rec_id_map: &HashMap<u64, (u32, u64)>, vlsd_rec_id: &HashSet<u64>) -> Result<HashMap<u64, Vec<u64>>, Mf4Error> {
+        // scan all records of an unsorted data group; record id -> virtual offsets of record data
+        let data_length = data_block.get_data_len();
+        let mut offsets_map: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut cur_off: u64 = 0; // virtual offset always start from 0
+        while cur_off < data_length {
+            let (rec_id, id_size) = read_rec_id(rec_id_size, data_block, buf, cur_off)?;
+            cur_off += id_size as u64;
+            offsets_map.entry(rec_id)
+                   .and_modify(|v| v.push(cur_off))
+                   .or_insert(vec![cur_off]);
+            let bytes_to_skip: u32 = if !vlsd_rec_id.contains(&rec_id)  {
+                rec_id_map.get(&rec_id).ok_or(format!("Unknown record id {} at {}", rec_id, cur_off))?.0  // skip this record's data field
+            } else {   // handler vlsd records
+                let mut four_bytes = [0u8; 4];
+                data_block.read_virtual_buf(buf, cur_off, &mut four_bytes)?;
+                let vlsd_len = u32::from_le_bytes(four_bytes);
+                4 + vlsd_len
+            };
+            cur_off += bytes_to_skip as u64;
+        }
+        Ok(offsets_map)
+    }
+
+    fn create_rec_id_map(channel_groups: &[ChannelGroup], column_oriented: bool) -> HashMap<u64, (u32, u64)> {
+        // record id -> (record bytes, cycle count)
+        let mut rec_id_map: HashMap<u64, (u32, u64)> = HashMap::new();
+        channel_groups.iter().for_each(|cg| {
+            // invalidation bytes are not part of the record for column oriented data groups
+            let rec_bytes: u32 = if column_oriented { cg.get_data_bytes() } else { cg.get_sample_total_bytes() };
+            rec_id_map.insert(cg.get_record_id(), 
+            (rec_bytes, cg.get_cycle_count()));
+        });
+        rec_id_map
+    }
+
+    fn read_record_blocks(buf: &mut Cursor<&[u8]>, data: u64, column_oriented: bool, dz_cache: &DzCache)
+            -> Result<DataAndInval, Mf4Error> {
+        // data block of records and invalidation bits of column oriented data groups
+        if column_oriented {
+            read_column_blocks(buf, data, dz_cache)
+        } else {
+            Ok((read_data_block_with_cache(buf, data, dz_cache)?, None))
+        }
+    }
+
     impl DataGroup {
-        pub fn new_unchecked(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError> {
-            let dg_desc: &crate::block::BlockDesc = get_block_desc_by_name("DG".to_string()).unwrap();
+        pub fn new_unchecked(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_unchecked_with_cache(buf, offset, new_dz_cache(DEFAULT_DZ_CACHE_SIZE))
+        }
+
+        pub fn new_unchecked_with_cache(buf: &mut Cursor<&[u8]>, offset: u64, dz_cache: DzCache) -> Result<Self, Mf4Error> {
+            let dg_desc: &crate::block::BlockDesc = get_block_desc_by_name("DG".to_string()).ok_or("DG block not found")?;
             let info: crate::block::BlockInfo = dg_desc.try_parse_buf(buf, offset)?;
             let rec_id_size: RecIDSize = match info.get_data_value_first::<u8>("dg_rec_id_size") {
                 Some(0) => RecIDSize::NORECID,
@@ -104,78 +161,161 @@ pub mod datagroup {
                 Some(8) => RecIDSize::UINT64,
                 _ => return Err("Unknown rec_id_size".into())
             };
-            let comment: String = get_clean_text(buf, info.get_link_offset_normal("dg_md_comment").unwrap())
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("dg_md_comment").unwrap_or(0))
                                                             .unwrap_or("".to_string());
-            let data: u64 = info.get_link_offset_normal("dg_data").unwrap();
-            let cg_links: Vec<u64> = get_child_links(buf, 
-                                    info.get_link_offset_normal("dg_cg_first").unwrap(), "CG").unwrap();
+            let data: u64 = info.get_link_offset_normal("dg_data").unwrap_or(0);
+            let cg_first: u64 = info.get_link_offset_normal("dg_cg_first").unwrap_or(0);
+            let cg_links: Vec<u64> = if cg_first != 0 {
+                get_child_links(buf, cg_first, "CG")?
+            } else { Vec::new() };   // data group without any channel group
             let mut channel_groups: Vec<ChannelGroup> = Vec::new();
             for link in cg_links {
                 let cg: ChannelGroup = ChannelGroup::new(buf, link)?; // fails to construct the whole dg if any cg parsing error exists
                 channel_groups.push(cg);
             }
-            let sorted: bool = match channel_groups.len() {
-                0 | 1 => true,
-                _ => false
-            };
-            let mut rec_id_map: HashMap<u64, (u32, u64)> = HashMap::new();
-            let mut vlsd_rec_id: HashSet<u64> = HashSet::new();
-            channel_groups.iter().for_each(|cg| {
-                rec_id_map.insert(cg.get_record_id(), 
-                (cg.get_sample_total_bytes(), cg.get_cycle_count()));
-                if cg.is_vlsd() {
-                    vlsd_rec_id.insert(cg.get_record_id());
-                }
-            });
-            let data_block = read_data_block(buf, data)?;
-            let data_length = data_block.get_data_len(); // skip link_count
-            let mut offsets_map: HashMap<u64, Vec<u64>> = HashMap::new();
-            if !sorted {  // if dg is not sorted, we need to read all the records to get the offsets to improve runtime performance
-                let mut cur_off: u64 = 0; // virtual offset always start from 0
-                while cur_off < data_length {
-                    let (rec_id, id_size) = read_rec_id(rec_id_size, &data_block, buf, cur_off)?;
-                    cur_off += id_size as u64;
-                    offsets_map.entry(rec_id)
-                           .and_modify(|v| v.push(cur_off))
-                           .or_insert(vec![cur_off]);
-                    let bytes_to_skip: u32 = if !vlsd_rec_id.contains(&rec_id)  { 
-                        rec_id_map.get(&rec_id).unwrap().0  // skip this record's data field
-                    } else {   // handler vlsd records
-                        let mut four_bytes = [0u8; 4];
-                        data_block.read_virtual_buf(buf, cur_off, &mut four_bytes)?;
-                        let vlsd_len = u32::from_le_bytes(four_bytes);
-                        4 + vlsd_len
-                    };
-                    cur_off += bytes_to_skip as u64;
+            let mut template_groups: Vec<(u64, ChannelGroup)> = Vec::new();
+            let mut element_groups: Vec<(Option<u64>, ChannelGroup)> = Vec::new();
+            for cg in channel_groups.iter() {
+                element_groups.extend(cg.generate_template_groups()?);
+            }
+            let declared_rec_ids: HashSet<u64> = channel_groups.iter().map(|cg| cg.get_record_id()).collect();
+            for (data_link, cg) in element_groups {
+                match data_link {
+                    Some(data_link) => template_groups.push((data_link, cg)),
+                    None if declared_rec_ids.contains(&cg.get_record_id()) => return Err(Mf4Error::Malformed {
+                        reason: format!("record id {} of CG template array element is used by another channel group", cg.get_record_id()),
+                        offset: Some(cg.get_offset()),
+                    }),
+                    None => channel_groups.push(cg),   // CG template elements are in this data group
                 }
             }
-            Ok(Self { 
+            let column_oriented: bool = data != 0 && is_column_oriented(buf, data);
+            let rec_id_map: HashMap<u64, (u32, u64)> = create_rec_id_map(&channel_groups, column_oriented);
+            let sorted: bool = rec_id_map.len() <= 1;
+            let (data_block, inval_block) = read_record_blocks(buf, data, column_oriented, &dz_cache)?;
+            let mut dg = Self {
                 rec_id_size, 
                 comment, 
                 data, 
                 channel_groups,   
                 sorted,
                 rec_id_map,
-                offsets_map,
-                data_block
-             })
+                offsets_map: OnceLock::new(),
+                data_block,
+                column_oriented,
+                inval_block,
+                dz_cache,
+                offset,
+                element_groups: Vec::new(),
+            };
+            for (data_link, cg) in template_groups {
+                let element: DataGroup = dg.new_template_element(buf, data_link, cg)?;
+                dg.element_groups.push(element);
+            }
+            Ok(dg)
+        }
+
+        fn new_template_element(&self, buf: &mut Cursor<&[u8]>, data: u64, cg: ChannelGroup) -> Result<Self, Mf4Error> {
+            /* records of one DG template array element; they have the layout of this data group and are stored in data block of ca_data */
+            let column_oriented: bool = data != 0 && is_column_oriented(buf, data);
+            let channel_groups: Vec<ChannelGroup> = vec![cg];
+            let rec_id_map: HashMap<u64, (u32, u64)> = create_rec_id_map(&channel_groups, column_oriented);
+            let (data_block, inval_block) = read_record_blocks(buf, data, column_oriented, &self.dz_cache)?;
+            Ok(Self {
+                rec_id_size: self.rec_id_size,
+                comment: self.comment.clone(),
+                data,
+                channel_groups,
+                sorted: true,
+                rec_id_map,
+                offsets_map: OnceLock::new(),
+                data_block,
+                column_oriented,
+                inval_block,
+                dz_cache: self.dz_cache.clone(),
+                offset: self.offset,
+                element_groups: Vec::new(),
+            })
         }
 
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError> {
+        pub fn new_v3(buf: &mut Cursor<&[u8]>, offset: u64, big_endian: bool) -> Result<Self, Mf4Error> {
+            /* MDF 3.x data group; records are stored without any data block header */
+            let dg_desc: &crate::block::BlockDesc = get_block_desc_v3_by_name("DG".to_string()).ok_or("DG block not found")?;
+            let info: crate::block::BlockInfo = dg_desc.try_parse_buf_v3(buf, offset)?;
+            let rec_id_number: u16 = info.get_data_value_first("dg_rec_id_number").ok_or("dg_rec_id_number not found")?;
+            let rec_id_size: RecIDSize = match rec_id_number {
+                0 => RecIDSize::NORECID,
+                1 | 2 => RecIDSize::UINT8,
+                _ => return Err("Unknown number of record ids".into())
+            };
+            let data: u64 = info.get_link_offset_normal("dg_data").ok_or("dg_data not found")?;
+            let cg_links: Vec<u64> = get_child_links_v3(buf,
+                                    info.get_link_offset_normal("dg_cg_first").ok_or("dg_cg_first not found")?, "CG")?;
+            let mut channel_groups: Vec<ChannelGroup> = Vec::new();
+            for link in cg_links {
+                channel_groups.push(ChannelGroup::new_v3(buf, link, big_endian)?);
+            }
+            let sorted: bool = channel_groups.len() <= 1 && rec_id_number == 0;  // records with ids have to be indexed
+            let mut rec_id_map: HashMap<u64, (u32, u64)> = HashMap::new();
+            let mut data_length: u64 = 0;
+            for cg in channel_groups.iter() {
+                // with 2 record ids, the record id is repeated after the record; treat it as part of the record
+                let rec_bytes: u32 = cg.get_sample_total_bytes() + if rec_id_number == 2 { 1 } else { 0 };
+                rec_id_map.insert(cg.get_record_id(), (rec_bytes, cg.get_cycle_count()));
+                data_length = (rec_bytes as u64 + if rec_id_number > 0 { 1 } else { 0 })
+                                .checked_mul(cg.get_cycle_count())
+                                .and_then(|len| data_length.checked_add(len))
+                                .ok_or("Data corrupted: record length overflow")?;
+            }
+            // records have no block header that could be checked, so the file has to hold all of them
+            if data.checked_add(data_length).is_none_or(|end| end > buf.get_ref().len() as u64) {
+                return Err(Mf4Error::Malformed {
+                    reason: format!("records of {} bytes exceed the file", data_length),
+                    offset: Some(offset),
+                });
+            }
+            let data_block: Box<dyn VirtualBuf> = Box::new(DT::new_raw(data, data_length));
+            Ok(Self {
+                rec_id_size,
+                comment: String::new(),
+                data,
+                channel_groups,
+                sorted,
+                rec_id_map,
+                offsets_map: OnceLock::new(),
+                data_block,
+                column_oriented: false,
+                inval_block: None,
+                dz_cache: new_dz_cache(1),   // no compression in MDF 3.x
+                offset,
+                element_groups: Vec::new(),
+            })
+        }
+
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
             let dg = Self::new_unchecked(buf, offset)?;
+            dg.check_records(buf)?;
+            for element in dg.element_groups.iter() {
+                element.check_records(buf)?;
+            }
+            Ok(dg)
+        }
+
+        fn check_records(&self, buf: &mut Cursor<&[u8]>) -> Result<(), Mf4Error> {
             let mut cur_off: u64 = 0; // virtual offset always start from 0
             let mut cycle_count_map: HashMap<u64, u64> = HashMap::new();
-            while cur_off < dg.data_block.get_data_len() {
+            while cur_off < self.data_block.get_data_len() {
                 let rec_id: u64;
                 let id_size: u8;
-                if !dg.sorted {
-                    (rec_id, id_size) = read_rec_id(dg.rec_id_size, &dg.data_block, buf, cur_off)?;
+                if !self.sorted {
+                    (rec_id, id_size) = read_rec_id(self.rec_id_size, self.data_block.as_ref(), buf, cur_off)?;
                 } else {
-                    rec_id = dg.channel_groups.get(0).and_then(|cg| Some(cg.get_record_id())).or(Some(0u64)).unwrap();
+                    rec_id = self.channel_groups.first().map(|cg| cg.get_record_id()).unwrap_or(0);
                     id_size = 0u8;
                 }
                 cur_off += id_size as u64;
-                let bytes_to_skip: u32 = dg.rec_id_map.get(&rec_id).unwrap().0;  // skip this record's data field
+                let bytes_to_skip: u32 = self.rec_id_map.get(&rec_id)
+                                        .ok_or_else(|| format!("Data corrupted: unknown record id {}.", rec_id))?.0;  // skip this record's data field
                 cur_off += bytes_to_skip as u64;
                 cycle_count_map.entry(rec_id)
                                .and_modify(|v| {*v += 1})
@@ -184,11 +324,11 @@ pub mod datagroup {
             
             // check if cycle count is valid
             for (rec_id, cycle_count) in cycle_count_map.iter() {
-                if dg.rec_id_map.get(rec_id).unwrap().1 != *cycle_count {
+                if self.rec_id_map.get(rec_id).map(|v| v.1) != Some(*cycle_count) {
                     return Err("Data corrupted: Invalid record cycle count.".into());
                 }
             }
-            Ok(dg)
+            Ok(())
         }
 
         pub fn create_map(&self) -> HashMap<String, ChannelLink>{
@@ -201,33 +341,93 @@ pub mod datagroup {
             cn_link_map
         }
 
-        fn get_rec_id_offset(&self, rec_id: u64, cycle_index: u64) -> Option<u64> {
+        fn get_offsets_map(&self, file: &mut Cursor<&[u8]>) -> Result<&HashMap<u64, Vec<u64>>, Mf4Error> {
+            // records of an unsorted data group are scanned once, when data is read for the first time
+            if let Some(offsets_map) = self.offsets_map.get() {
+                return Ok(offsets_map);
+            }
+            let vlsd_rec_id: HashSet<u64> = self.channel_groups.iter()
+                                .filter(|cg| cg.is_vlsd()).map(|cg| cg.get_record_id()).collect();
+            let offsets_map = index_records(file, self.rec_id_size, self.data_block.as_ref(), &self.rec_id_map, &vlsd_rec_id)
+                                .map_err(|e| e.with_offset(self.offset))?;
+            Ok(self.offsets_map.get_or_init(|| offsets_map))
+        }
+
+        fn get_rec_id_offset(&self, rec_id: u64, cycle_index: u64, file: &mut Cursor<&[u8]>) -> Result<u64, Mf4Error> {
             if !self.sorted {
-                self.offsets_map.get(&rec_id)
-                                .and_then(|x| x.get(cycle_index as usize).and_then(|y| Some(y.clone())))
+                self.get_offsets_map(file)?
+                    .get(&rec_id)
+                    .and_then(|x| x.get(cycle_index as usize).copied())
+                    .ok_or("Record not found".into())
             } else {
-                let bytes_num = self.rec_id_map.get(&rec_id)?.0;
-                Some((cycle_index * bytes_num as u64) as u64)
+                let bytes_num = self.rec_id_map.get(&rec_id).ok_or("Record not found")?.0;
+                Ok(cycle_index * bytes_num as u64)
             }
         }
 
+        pub fn is_indexed(&self) -> bool {
+            // sorted data groups need no record index
+            self.sorted || self.offsets_map.get().is_some()
+        }
+
         pub fn get_cg_data(&self, rec_id: u64, index: u64, file: &mut Cursor<&[u8]>) -> Option<Vec<u8>> {
-            let virtual_offset = self.get_rec_id_offset(rec_id, index)?;
+            let virtual_offset = self.get_rec_id_offset(rec_id, index, file).ok()?;
             let data_block: &Box<dyn VirtualBuf> = &self.data_block;
             let mut temp_buf = vec![0u8; self.rec_id_map.get(&rec_id)?.0 as usize];
             data_block.read_virtual_buf(file, virtual_offset, &mut temp_buf[..]).ok()?;
             Some(temp_buf)
         }
 
-        pub fn get_cn_bytes<'a>(&'a self, rec_id: u64, index: u64, file: &'a mut Cursor<&[u8]>, cn: &'a Channel) -> Result<Cow<'a, [u8]>, DynError> {
-            let virtual_offset = self.get_rec_id_offset(rec_id, index).ok_or("Record not found")? + cn.get_byte_offset() as u64;
+        pub fn get_cn_bytes<'a>(&'a self, rec_id: u64, index: u64, file: &'a mut Cursor<&[u8]>, cn: &'a Channel) -> Result<Cow<'a, [u8]>, Mf4Error> {
+            let virtual_offset = self.get_rec_id_offset(rec_id, index, file)? + cn.get_byte_offset() as u64;
             let data_block: &Box<dyn VirtualBuf> = &self.data_block;
             data_block.get_data_ref(file, virtual_offset, cn.get_bytes_num() as usize)
         }
 
+        pub fn for_each_cn_bytes(&self, file: &mut Cursor<&[u8]>, cg: &ChannelGroup, cn: &Channel,
+                                 f: &mut dyn FnMut(&[u8]) -> Result<(), Mf4Error>) -> Result<(), Mf4Error> {
+            // bytes of a channel in every record; records of sorted data groups are read block by block
+            let rec_id: u64 = cg.get_record_id();
+            if self.sorted {
+                let rec_bytes: u64 = self.rec_id_map.get(&rec_id).ok_or("Record not found")?.0 as u64;
+                self.data_block.for_each_strided(file, cn.get_byte_offset() as u64, rec_bytes,
+                                                 cn.get_bytes_num() as usize, cg.get_cycle_count(), f)
+            } else {
+                for i in 0..cg.get_cycle_count() {
+                    f(&self.get_cn_bytes(rec_id, i, file, cn)?)?;
+                }
+                Ok(())
+            }
+        }
+
+        pub fn get_inval_bit(&self, cg: &ChannelGroup, index: u64, file: &mut Cursor<&[u8]>, bit_pos: u32) -> Result<bool, Mf4Error> {
+            // true means the value is invalid
+            let mut byte = [0u8; 1];
+            if self.column_oriented {
+                // invalidation bytes of all records are stored in DI blocks
+                let inval_block = self.inval_block.as_ref().ok_or("No invalidation data block")?;
+                let virtual_offset = index * cg.get_invalid_bytes() as u64 + (bit_pos / 8) as u64;
+                inval_block.read_virtual_buf(file, virtual_offset, &mut byte)?;
+            } else {
+                // invalidation bytes follow the data bytes of each record
+                let virtual_offset = self.get_rec_id_offset(cg.get_record_id(), index, file)?
+                                     + cg.get_data_bytes() as u64 + (bit_pos / 8) as u64;
+                self.data_block.read_virtual_buf(file, virtual_offset, &mut byte)?;
+            }
+            Ok(byte[0] & (0x01 << (bit_pos % 8)) != 0)
+        }
+
+        pub fn get_column_bytes<'a>(&'a self, file: &'a mut Cursor<&[u8]>, cg: &ChannelGroup) -> Result<Cow<'a, [u8]>, Mf4Error> {
+            // all values of a column oriented channel group in one piece
+            if !self.column_oriented {
+                return Err("Data group is not column oriented".into());
+            }
+            let len = cg.get_data_bytes() as u64 * cg.get_cycle_count();
+            self.data_block.get_data_ref(file, 0, len as usize)
+        }
 
         pub fn get_vlsd_cg_data(&self, rec_id: u64, index: u64, file: &mut Cursor<&[u8]>) -> Option<Vec<u8>> {
-            let virtual_offset = self.get_rec_id_offset(rec_id, index)?;
+            let virtual_offset = self.get_rec_id_offset(rec_id, index, file).ok()?;
             let data_block: &Box<dyn VirtualBuf> = &self.data_block;
             let mut four_bytes: [u8; 4] = [0u8; 4];
             data_block.read_virtual_buf(file, virtual_offset, &mut four_bytes[..]).ok()?;
@@ -251,6 +451,14 @@ pub mod datagroup {
             self.sorted
         }
 
+        pub fn is_column_oriented(&self) -> bool {
+            self.column_oriented
+        }
+
+        pub fn get_dz_cache(&self) -> &DzCache {
+            &self.dz_cache
+        }
+
         pub fn get_comment(&self) -> &str {
             &self.comment
         }
@@ -267,6 +475,15 @@ pub mod datagroup {
             self.channel_groups.get(n)
         }
 
+        pub fn get_offset(&self) -> u64 {
+            self.offset
+        }
+
+        pub fn take_element_groups(&mut self) -> Vec<DataGroup> {
+            // data groups of DG template array elements; they are listed next to their template in Mdf
+            std::mem::take(&mut self.element_groups)
+        }
+
     }
 
     impl Display for DataGroup {
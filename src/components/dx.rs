@@ -1,4 +1,4 @@
-/* DL DV DT SD RD blocks
+/* DL LD DV DI DT SD RD blocks
 */
 
 pub mod dataxxx {
@@ -9,19 +9,33 @@ pub mod dataxxx {
     use crate::block::{BlockInfo, BlockDesc};
     use std::cell::Cell;
     use std::borrow::Cow;
+    use std::num::NonZeroUsize;
+    use std::sync::{Arc, Mutex};
+    use lru::LruCache;
 
 
-    type DynError = Box<dyn std::error::Error>;
+    use crate::error::Mf4Error;
+    pub type DataAndInval = (Box<dyn VirtualBuf>, Option<Box<dyn VirtualBuf>>);  // values and invalidation bits
 
     /* This trait should be implemented to DT SD and RD DL blocks
        This trait is used to read physically incontinuous data block linked by DL block*/
     pub trait VirtualBuf{
         fn read_virtual_buf(&self, from: &mut Cursor<&[u8]>, virtual_offset:u64, buf: &mut [u8]) 
-            -> Result<(), DynError>;
+            -> Result<(), Mf4Error>;
         
         fn get_data_len(&self) -> u64;
         // no copy version of read_virtual_buf to reduce copy cost
-        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor<&[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, DynError>;
+        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor<&[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, Mf4Error>;
+
+        /* calls f with `count` pieces of `len` bytes that start `stride` bytes apart, e.g. one channel in sorted records;
+           blocks that have to look up their data (DZ) should hand out all pieces of one lookup */
+        fn for_each_strided(&self, from: &mut Cursor<&[u8]>, virtual_offset: u64, stride: u64, len: usize, count: u64,
+                            f: &mut dyn FnMut(&[u8]) -> Result<(), Mf4Error>) -> Result<(), Mf4Error> {
+            for i in 0..count {
+                f(&self.get_data_ref(from, virtual_offset + i * stride, len)?)?;
+            }
+            Ok(())
+        }
     }
     #[derive(Debug, Default)]
     pub struct DT{
@@ -30,8 +44,8 @@ pub mod dataxxx {
     }
 
     impl DT{
-        /* This should also works for SD and RD blocks; they have samilar data structure  */
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError>{
+        /* This should also works for SD, RD, DV and DI blocks; they have samilar data structure  */
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error>{
             if offset == 0 {
                 return Err("Invalid data block offset: 0".into());
             }
@@ -43,22 +57,29 @@ pub mod dataxxx {
             buf.read_exact(&mut buffer)?;
             buf.seek(SeekFrom::Current(8))?; // skip 8 bytes that are link len
             Ok(DT{
-                data_len: u64::from_le_bytes(buffer) - 24,  // without header
+                data_len: u64::from_le_bytes(buffer).checked_sub(24).ok_or("Invalid data block length")?,  // without header
                 data_offset: buf.stream_position()?,
             })
 
         }
+
+        pub fn new_raw(data_offset: u64, data_len: u64) -> Self {
+            /* MDF 3.x data block has no header; records start directly at data_offset */
+            DT{
+                data_len,
+                data_offset,
+            }
+        }
     }
 
     impl VirtualBuf for DT{
-        fn read_virtual_buf(&self, from: &mut Cursor<&[u8]>, virtual_offset:u64, buf: &mut [u8]) -> Result<(), DynError> {
-            let file_offset = self.data_offset + virtual_offset;
-            from.seek(SeekFrom::Start(file_offset))?;
-            let left_bytes_num = self.data_len - virtual_offset;
+        fn read_virtual_buf(&self, from: &mut Cursor<&[u8]>, virtual_offset:u64, buf: &mut [u8]) -> Result<(), Mf4Error> {
+            let left_bytes_num = self.data_len.checked_sub(virtual_offset).ok_or("Not enough bytes in the block")?;
             let data_to_read = buf.len();
-            if data_to_read > left_bytes_num as usize {
+            if data_to_read as u64 > left_bytes_num {
                 Err("Not enough bytes in the block".into())
             } else {
+                from.seek(SeekFrom::Start(self.data_offset + virtual_offset))?;
                 from.read_exact(buf)?;
                 Ok(())
             }
@@ -68,50 +89,74 @@ pub mod dataxxx {
             self.data_len
         }
 
-        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor< &[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, DynError> {
-            let buf = from.get_ref();
-            let file_offset = self.data_offset + virtual_offset;
-            Ok(Cow::Borrowed(&buf[file_offset as usize..file_offset as usize + len]))
+        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor< &[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, Mf4Error> {
+            // the piece has to lie in this block and in the file
+            if virtual_offset.checked_add(len as u64).is_none_or(|end| end > self.data_len) {
+                return Err("Not enough bytes in the block".into());
+            }
+            let file_offset = (self.data_offset + virtual_offset) as usize;
+            let buf: &[u8] = from.get_ref();
+            Ok(Cow::Borrowed(buf.get(file_offset..file_offset + len).ok_or("Not enough bytes in the block")?))
         }
     }
+    pub type DzCache = Arc<Mutex<LruCache<u64, Arc<Vec<u8>>>>>;   // dz block offset -> decompressed data
+
+    pub const DEFAULT_DZ_CACHE_SIZE: usize = 16;  // number of decompressed blocks kept in memory
+
+    pub fn new_dz_cache(capacity: usize) -> DzCache {
+        // one cache should only be shared by blocks of the same file
+        Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(capacity.max(1)).unwrap())))
+    }
+
     #[derive(Debug)]
     pub struct DZBlock {
-        // this will save decompressed data into memory; could consume a lot of memory if the mf4 file contains a lot of dz blocks
+        // data is decompressed on demand; only the recently used blocks are kept in the cache
+        offset: u64,   // block offset in file; also the key in cache
         data_len: u64,  // compressed data length
         ori_data_len: u64,
-        ori_data: Vec<u8>, // original data; decompressed data
+        org_block_type: String,  // DT, SD, DV, DI ...
+        zip_type: u8,
+        zip_parameter: u32,
+        cache: DzCache,
     }
 
     impl DZBlock {
-        pub fn new(file: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError> {
-            let dz_desc: &BlockDesc = get_block_desc_by_name("DZ".to_string()).unwrap();
-            let mut dz_info: BlockInfo = dz_desc.try_parse_buf(file, offset)?;
-            let data_len: u64 = dz_info.get_data_value_first::<u64>("dz_data_length")
-                                     .ok_or("Cannot find data length")?;
-            let ori_data_len: u64 = dz_info.get_data_value_first::<u64>("dz_org_data_length")
-                                     .ok_or("Cannot find original data length")?;
-            let zip_type: u8 = dz_info.get_data_value_first::<u8>("dz_zip_type").ok_or("Cannot find zip type")?;
-            if zip_type != 0 {
-                return Err("Unsupported compression type".into());
+        pub fn new(file: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            Self::new_with_cache(file, offset, new_dz_cache(1))
+        }
+
+        pub fn new_with_cache(file: &mut Cursor<&[u8]>, offset: u64, cache: DzCache) -> Result<Self, Mf4Error> {
+            Self::read_header(file, offset, cache).map_err(|e| e.with_offset(offset))
+        }
+
+        fn read_header(file: &mut Cursor<&[u8]>, offset: u64, cache: DzCache) -> Result<Self, Mf4Error> {
+            // only the header is read here; compressed data is not touched until it is needed
+            if peek_block_type(file, offset)? != "DZ" {
+                return Err(Mf4Error::corrupt_link(offset));
             }
-            if let Some(data) = dz_info.retrieve_data_value("unparsed_data") {
-                let raw_data: Vec<u8> = data.try_into()?;
-                let mut decoder: ZlibDecoder<&[u8]> = ZlibDecoder::new(&raw_data[..]);
-                let mut ori_data: Vec<u8> = Vec::new();
-                decoder.read_to_end(&mut ori_data)?;
-                if ori_data.len() as u64 != ori_data_len {
-                    Err(format!("Invalid de-compressed data length for dz block at {}", offset).into())
-                } else {
-                    Ok(Self {
-                        data_len,
-                        ori_data_len,
-                        ori_data,
-                    })
-                }
-            } else {
-                Err("Dzblock does not contain any data.".into())
+            file.seek(SeekFrom::Start(offset + 24))?;  // skip block header
+            let mut header = [0u8; 24];
+            file.read_exact(&mut header)?;
+            let org_block_type: String = String::from_utf8(header[0..2].to_vec())?;
+            let zip_type: u8 = header[2];
+            let zip_parameter: u32 = u32::from_le_bytes(header[4..8].try_into().unwrap());
+            let ori_data_len: u64 = u64::from_le_bytes(header[8..16].try_into().unwrap());
+            let data_len: u64 = u64::from_le_bytes(header[16..24].try_into().unwrap());
+            if zip_type > 1 {
+                return Err(Mf4Error::unsupported(format!("compression type {}", zip_type)));
+            }
+            if offset.checked_add(48).and_then(|x| x.checked_add(data_len)).is_none_or(|end| end > file.get_ref().len() as u64) {
+                return Err("Compressed data of dz block is out of file range".into());
             }
-            
+            Ok(Self {
+                offset,
+                data_len,
+                ori_data_len,
+                org_block_type,
+                zip_type,
+                zip_parameter,
+                cache,
+            })
         }
 
         fn get_orig_len(&self) -> u64 {
@@ -122,28 +167,86 @@ pub mod dataxxx {
             self.data_len
         }
 
-        pub fn get_data(&self) -> &[u8] {
-            &self.ori_data
+        pub fn get_data(&self, file: &mut Cursor<&[u8]>) -> Result<Arc<Vec<u8>>, Mf4Error> {
+            // decompressed data, from cache if possible
+            if let Some(data) = self.cache.lock().map_err(|_| "DZ cache poisoned")?.get(&self.offset) {
+                return Ok(data.clone());
+            }
+            let data = Arc::new(self.decompress(file).map_err(|e| e.with_offset(self.offset))?);
+            self.cache.lock().map_err(|_| "DZ cache poisoned")?.put(self.offset, data.clone());
+            Ok(data)
         }
+
+        fn decompress(&self, file: &mut Cursor<&[u8]>) -> Result<Vec<u8>, Mf4Error> {
+            let start = (self.offset + 48) as usize;
+            let raw_data: &[u8] = start.checked_add(self.data_len as usize)
+                                       .and_then(|end| file.get_ref().get(start..end))
+                                       .ok_or("Compressed data of dz block is out of file range")?;
+            let decoder: ZlibDecoder<&[u8]> = ZlibDecoder::new(raw_data);
+            let mut ori_data: Vec<u8> = Vec::new();   // header length is not trusted for preallocation
+            decoder.take(self.ori_data_len.saturating_add(1)).read_to_end(&mut ori_data)?;
+            if self.zip_type == 1 {
+                ori_data = untranspose(&ori_data, self.zip_parameter as usize);
+            }
+            if ori_data.len() as u64 != self.ori_data_len {
+                return Err("Invalid de-compressed data length for dz block".into());
+            }
+            Ok(ori_data)
+        }
+
+        pub fn get_org_block_type(&self) -> &str {
+            &self.org_block_type
+        }
+    }
+
+    fn untranspose(data: &[u8], columns: usize) -> Vec<u8> {
+        /* transposed data is a columns x rows matrix; the remaining bytes which do not fill a row are not transposed */
+        if columns <= 1 || data.len() < columns {
+            return data.to_vec();
+        }
+        let rows = data.len() / columns;
+        let mut ori_data: Vec<u8> = vec![0u8; data.len()];
+        for col in 0..columns {
+            for row in 0..rows {
+                ori_data[row * columns + col] = data[col * rows + row];
+            }
+        }
+        ori_data[rows * columns..].copy_from_slice(&data[rows * columns..]);
+        ori_data
     }
 
     impl VirtualBuf for DZBlock {
         fn get_data_len(&self) -> u64 {
             self.get_orig_len()
         }
-        #[allow(unused_variables)]   // from is not needed for DZBlock
+
         fn read_virtual_buf(&self, from: &mut Cursor<&[u8]>, virtual_offset:u64, buf: &mut [u8]) 
-                    -> Result<(), DynError> {
-            let mut cur = Cursor::new(&self.ori_data);
-            cur.seek(SeekFrom::Start(virtual_offset))?;
-            cur.read_exact(buf)?;
+                    -> Result<(), Mf4Error> {
+            let data = self.get_data(from)?;
+            let offs = virtual_offset as usize;
+            if offs + buf.len() > data.len() {
+                return Err("Not enough bytes in the block".into());
+            }
+            buf.copy_from_slice(&data[offs..offs+buf.len()]);
             Ok(())
         }
-        #[allow(unused_variables)]
-        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor< &[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, DynError>{
-            let buf: &[u8] = self.ori_data.as_ref();
-            let offs = virtual_offset as usize;
-            Ok(Cow::Borrowed(&buf[offs..offs+len]))
+
+        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor< &[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, Mf4Error>{
+            // decompressed data is shared with the cache, so it has to be copied out; use for_each_strided for many records
+            let mut buf: Vec<u8> = vec![0u8; len];
+            self.read_virtual_buf(from, virtual_offset, &mut buf)?;
+            Ok(Cow::Owned(buf))
+        }
+
+        fn for_each_strided(&self, from: &mut Cursor<&[u8]>, virtual_offset: u64, stride: u64, len: usize, count: u64,
+                            f: &mut dyn FnMut(&[u8]) -> Result<(), Mf4Error>) -> Result<(), Mf4Error> {
+            // one cache lookup for all pieces; they are borrowed from the decompressed data
+            let data = self.get_data(from)?;
+            for i in 0..count {
+                let start = (virtual_offset + i * stride) as usize;
+                f(data.get(start..start + len).ok_or("Not enough bytes in the block")?)?;
+            }
+            Ok(())
         }
     }
     #[derive(Debug)]
@@ -160,6 +263,16 @@ pub mod dataxxx {
         dl_distance_values: Option<Vec<u64>>,
     }
 
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct LDBlock {
+        ld_ld_next: u64,
+        ld_data: Vec<u64>,
+        ld_inval_data: Vec<u64>,   // empty if no invalidation data
+        ld_flags: u32,
+        ld_count: u32,
+    }
+
     pub struct DataLink {
         /* this struct will collect DL block links into one bulk */
         total_len: u64,
@@ -170,14 +283,14 @@ pub mod dataxxx {
         last_index: Cell<usize>,
     }
 
-    fn read_dl_block(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<DLBlock, DynError> {
+    fn read_dl_block(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<DLBlock, Mf4Error> {
         /* helper function to read DL block info to construct DataLink */
         buf.seek(SeekFrom::Start(offset))?;
         let mut buffer = [0u8; 4];
         buf.read_exact(&mut buffer)?;
 
-        if String::from_utf8(buffer.to_vec()).unwrap() != "##DL"{
-            return Err("Invalid DL block".into());
+        if &buffer != b"##DL" {
+            return Err(Mf4Error::corrupt_link(offset));
         } else {
             buf.seek(SeekFrom::Current(4))?; // skip 4 reserved bytes
             let mut eight_bytes = [0u8; 8];
@@ -188,7 +301,7 @@ pub mod dataxxx {
             buf.read_exact(&mut eight_bytes)?;
             let dl_dl_next = u64::from_le_bytes(eight_bytes);
             let mut dl_data = Vec::new();
-            for _ in 0..link_len-1 {
+            for _ in 0..link_len.saturating_sub(1) {
                 buf.read_exact(&mut eight_bytes)?;
                 dl_data.push(u64::from_le_bytes(eight_bytes));
             };
@@ -203,40 +316,40 @@ pub mod dataxxx {
             };
             let dl_offset = if dl_flags & 0x01 == 0x00 {
                 let mut v:Vec<u64> = Vec::new();
-                (0..dl_count).for_each(|_| {
-                    buf.read_exact(&mut eight_bytes).unwrap();
+                for _ in 0..dl_count {
+                    buf.read_exact(&mut eight_bytes)?;
                     v.push(u64::from_le_bytes(eight_bytes));
-                });
+                }
                 Some(v)
             } else {
                 None
             };
             let dl_time_values = if dl_flags & 0x02 == 0x02 {
                 let mut v = Vec::new();
-                (0..dl_count).for_each(|_| {
-                    buf.read_exact(&mut eight_bytes).unwrap();
+                for _ in 0..dl_count {
+                    buf.read_exact(&mut eight_bytes)?;
                     v.push(u64::from_le_bytes(eight_bytes));
-                });
+                }
                 Some(v)
             } else {
                 None
             };
             let dl_angle_values = if dl_flags & 0x04 == 0x04 {
                 let mut v = Vec::new();
-                (0..dl_count).for_each(|_| {
-                    buf.read_exact(&mut eight_bytes).unwrap();
+                for _ in 0..dl_count {
+                    buf.read_exact(&mut eight_bytes)?;
                     v.push(u64::from_le_bytes(eight_bytes));
-                });
+                }
                 Some(v)
             } else {
                 None
             };
             let dl_distance_values = if dl_flags & 0x08 == 0x08 {
                 let mut v = Vec::new();
-                (0..dl_count).for_each(|_| {
-                    buf.read_exact(&mut eight_bytes).unwrap();
+                for _ in 0..dl_count {
+                    buf.read_exact(&mut eight_bytes)?;
                     v.push(u64::from_le_bytes(eight_bytes));
-                });
+                }
                 Some(v)
             } else {
                 None
@@ -255,36 +368,55 @@ pub mod dataxxx {
         }
     }
 
+    fn read_ld_block(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<LDBlock, Mf4Error> {
+        /* helper function to read LD block of column oriented data groups; sample offsets and master values are skipped */
+        buf.seek(SeekFrom::Start(offset))?;
+        let mut buffer = [0u8; 4];
+        buf.read_exact(&mut buffer)?;
+        if &buffer != b"##LD" {
+            return Err(Mf4Error::corrupt_link(offset));
+        }
+        buf.seek(SeekFrom::Current(12))?; // skip 4 reserved bytes and block length
+        let mut eight_bytes = [0u8; 8];
+        buf.read_exact(&mut eight_bytes)?;
+        let link_len = u64::from_le_bytes(eight_bytes);
+        let mut links: Vec<u64> = Vec::new();
+        for _ in 0..link_len {
+            buf.read_exact(&mut eight_bytes)?;
+            links.push(u64::from_le_bytes(eight_bytes));
+        }
+        buf.read_exact(&mut eight_bytes)?;
+        let ld_flags = u32::from_le_bytes(eight_bytes[..4].try_into().unwrap());
+        let ld_count = u32::from_le_bytes(eight_bytes[4..].try_into().unwrap());
+        let has_inval: bool = ld_flags & (0x01 << 31) != 0;
+        let expected_links = 1 + ld_count as usize * if has_inval { 2 } else { 1 };
+        if links.len() < expected_links {
+            return Err(Mf4Error::from("Invalid link count of LD block").with_offset(offset));
+        }
+        let ld_data: Vec<u64> = links[1..1+ld_count as usize].to_vec();
+        let ld_inval_data: Vec<u64> = if has_inval {
+            links[1+ld_count as usize..expected_links].to_vec()
+        } else {
+            Vec::new()
+        };
+        Ok(LDBlock {
+            ld_ld_next: links[0],
+            ld_data,
+            ld_inval_data,
+            ld_flags,
+            ld_count,
+        })
+    }
+
     impl DataLink {
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError>{
-            let mut dl_blocks = Vec::new();
-            let mut cur_off = offset;
-            loop {
-                let dl_block = read_dl_block(buf, cur_off)?;
-                if dl_block.dl_dl_next == 0 {
-                    dl_blocks.push(dl_block);
-                    break;
-                } else {
-                    cur_off = dl_block.dl_dl_next;
-                    dl_blocks.push(dl_block);
-                }
-            }
-            let num_of_blocks = dl_blocks.iter()
-                        .fold(0, |acc, x| acc + x.dl_count as u64);
-            let start_offsets_in_file = dl_blocks.iter().flat_map(|x| {
-                x.dl_data.iter().map(|y| {
-                    *y
-                })
-            }).collect();
-            let mut data_blocks: Vec<Box<dyn VirtualBuf>> = Vec::with_capacity(num_of_blocks as usize);
-            for dl_block in dl_blocks.iter() {
-                for child_blocks in dl_block.dl_data.iter() {
-                    let block_type: String = peek_block_type(buf, *child_blocks).unwrap();
-                    match block_type.as_str() {
-                        "DT" => data_blocks.push(Box::new(DT::new(buf, *child_blocks).unwrap())),
-                        "DZ" => data_blocks.push(Box::new(DZBlock::new(buf, *child_blocks).unwrap())),
-                        _ => return Err("Unknown block type".into())  // should direct quit with error, otherwise will lead to discontinuous data
-                    }
+        fn from_child_blocks(buf: &mut Cursor<&[u8]>, child_offsets: Vec<u64>, cache: &DzCache) -> Result<Self, Mf4Error> {
+            let mut data_blocks: Vec<Box<dyn VirtualBuf>> = Vec::with_capacity(child_offsets.len());
+            for child_block in child_offsets.iter() {
+                let block_type: String = peek_block_type(buf, *child_block)?;
+                match block_type.as_str() {
+                    "DT" | "DV" | "DI" | "RD" | "RV" | "RI" => data_blocks.push(Box::new(DT::new(buf, *child_block)?)),
+                    "DZ" => data_blocks.push(Box::new(DZBlock::new_with_cache(buf, *child_block, cache.clone())?)),
+                    _ => return Err(Mf4Error::corrupt_link(*child_block))  // should direct quit with error, otherwise will lead to discontinuous data
                 }
             }
             let total_len:u64 = data_blocks.iter()
@@ -299,34 +431,74 @@ pub mod dataxxx {
                 v.pop();  // pop out the last item which is the total length of serval DT blocks
                 v
             };
+            Ok(DataLink{
+                total_len,
+                num_of_blocks: data_blocks.len() as u64,
+                start_offsets_in_file: child_offsets,
+                virtual_offsets,
+                data_blocks,
+                last_index: Cell::new(0),
+            })
+        }
+
+        pub fn new_column(buf: &mut Cursor<&[u8]>, offset: u64, cache: &DzCache) -> Result<(Self, Option<Self>), Mf4Error> {
+            /* LD list of column oriented data group; returns the values (DV) and the invalidation bits (DI) if there are any */
+            let mut data_links: Vec<u64> = Vec::new();
+            let mut inval_links: Vec<u64> = Vec::new();
+            let mut cur_off = offset;
+            while cur_off != 0 {
+                let ld_block = read_ld_block(buf, cur_off)?;
+                data_links.extend(ld_block.ld_data);
+                inval_links.extend(ld_block.ld_inval_data);
+                cur_off = ld_block.ld_ld_next;
+            }
+            let inval = if inval_links.is_empty() {
+                None
+            } else {
+                Some(Self::from_child_blocks(buf, inval_links, cache)?)
+            };
+            Ok((Self::from_child_blocks(buf, data_links, cache)?, inval))
+        }
+
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error>{
+            Self::new_with_cache(buf, offset, &new_dz_cache(DEFAULT_DZ_CACHE_SIZE))
+        }
+
+        pub fn new_with_cache(buf: &mut Cursor<&[u8]>, offset: u64, cache: &DzCache) -> Result<Self, Mf4Error>{
+            let mut dl_blocks = Vec::new();
+            let mut cur_off = offset;
+            loop {
+                let dl_block = read_dl_block(buf, cur_off)?;
+                if dl_block.dl_dl_next == 0 {
+                    dl_blocks.push(dl_block);
+                    break;
+                } else {
+                    cur_off = dl_block.dl_dl_next;
+                    dl_blocks.push(dl_block);
+                }
+            }
+            let child_offsets: Vec<u64> = dl_blocks.iter().flat_map(|x| {
+                x.dl_data.iter().copied()
+            }).collect();
+            let data_link = Self::from_child_blocks(buf, child_offsets, cache)?;
             /* verifiy offsets if not equal length */
             if dl_blocks[0].dl_flags & 0x01 == 0x00 {
                 // every dl block should be not equal length then
                 let equal_len = dl_blocks.iter().all(|x| x.dl_flags & 0x01 == 0x00);
                 if !equal_len {
-                    return Err("Not all dl blocks have the same equal length flag status.".into());
+                    return Err(Mf4Error::from("Not all dl blocks have the same equal length flag status.").with_offset(offset));
                 } else {
-                    let offs:Vec<u64> = dl_blocks.iter().flat_map(|x| {
-                        let v = x.dl_offset.as_ref().unwrap();
-                        v.iter().map(|y| {
-                            y.clone()
-                        })
-                    }).collect();
-                    for (left, right) in offs.iter().zip(virtual_offsets.iter()) {
+                    let offs:Vec<u64> = dl_blocks.iter()
+                        .flat_map(|x| x.dl_offset.iter().flatten().copied())
+                        .collect();
+                    for (left, right) in offs.iter().zip(data_link.virtual_offsets.iter()) {
                         if left != right {
-                            return Err("Offset not right for DL links.".into());
+                            return Err(Mf4Error::from("Offset not right for DL links.").with_offset(offset));
                         }
                     }
                 }
             }
-            Ok(DataLink{
-                total_len,
-                num_of_blocks,
-                start_offsets_in_file,
-                virtual_offsets,
-                data_blocks,
-                last_index: Cell::new(0),
-            })
+            Ok(data_link)
         }
 
         pub fn get_num_of_blocks(&self) -> u64 {
@@ -348,7 +520,7 @@ pub mod dataxxx {
 
     impl VirtualBuf for DataLink {
         fn read_virtual_buf(&self, from: &mut Cursor<&[u8]>, virtual_offset:u64, buf: &mut [u8]) 
-        -> Result<(), DynError> {
+        -> Result<(), Mf4Error> {
             let end_index = virtual_offset + buf.len() as u64;
             if end_index > self.total_len {
                 return Err("Virtual offset out of range.".into());
@@ -406,7 +578,7 @@ pub mod dataxxx {
             self.total_len
         }
 
-        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor<&[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, DynError> {
+        fn get_data_ref<'a>(&'a self, from: &'a mut Cursor<&[u8]>, virtual_offset:u64, len: usize) -> Result<Cow<'a, [u8]>, Mf4Error> {
             let end_index = virtual_offset + len as u64;
             if end_index > self.total_len {
                 return Err("Virtual offset out of range.".into());
@@ -458,6 +630,48 @@ pub mod dataxxx {
                 }
             }
         }
+
+        fn for_each_strided(&self, from: &mut Cursor<&[u8]>, virtual_offset: u64, stride: u64, len: usize, count: u64,
+                            f: &mut dyn FnMut(&[u8]) -> Result<(), Mf4Error>) -> Result<(), Mf4Error> {
+            /* pieces are handed to the blocks they lie in; only pieces across block borders are copied */
+            if stride < len as u64 {
+                for i in 0..count {
+                    f(&self.get_data_ref(from, virtual_offset + i * stride, len)?)?;
+                }
+                return Ok(())
+            }
+            let mut index: u64 = 0;
+            let mut piece: Vec<u8> = vec![0u8; len];
+            for (block, block_start) in self.data_blocks.iter().zip(self.virtual_offsets.iter()) {
+                let block_end: u64 = block_start + block.get_data_len();
+                let piece_start: u64 = virtual_offset + index * stride;
+                if index >= count {
+                    break;
+                }
+                if piece_start >= block_end {
+                    continue;
+                }
+                let in_block: u64 = if piece_start + len as u64 > block_end {
+                    0
+                } else {
+                    (block_end - len as u64 - piece_start).checked_div(stride)
+                                                          .map_or(count - index, |n| (n + 1).min(count - index))
+                };
+                block.for_each_strided(from, piece_start - block_start, stride, len, in_block, f)?;
+                index += in_block;
+                let piece_start: u64 = virtual_offset + index * stride;
+                if index < count && piece_start < block_end {
+                    // the next piece starts in this block and ends in a following one
+                    self.read_virtual_buf(from, piece_start, &mut piece)?;
+                    f(&piece)?;
+                    index += 1;
+                }
+            }
+            if index < count {
+                return Err("Virtual offset out of range.".into());
+            }
+            Ok(())
+        }
     }
 
     impl Display for DataLink {
@@ -475,15 +689,15 @@ pub mod dataxxx {
     }
 
     impl HL {
-        fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, DynError> {
-            let desc: &BlockDesc = get_block_desc_by_name("HL".to_string()).unwrap();
+        fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let desc: &BlockDesc = get_block_desc_by_name("HL".to_string()).ok_or("HL block not found")?;
             let hl_info: BlockInfo = desc.try_parse_buf(buf, offset)?;
             let hl_dl_first: u64 = hl_info.get_link_offset_normal("hl_dl_first")
                                           .ok_or("Can not find hl link hl_dl_first")?;
             let hl_flags: u16 = hl_info.get_data_value_first("hl_flags").ok_or("Cannot find hl_flags")?;
             let hl_zip_type: u8 = hl_info.get_data_value_first("hl_zip_type").ok_or("Cannot find hl_zip_type")?;
-            if hl_zip_type != 0 {
-                Err("Unsupported compression method. Only deflate is supported.".into())
+            if hl_zip_type > 1 {
+                Err(Mf4Error::unsupported(format!("compression type {}", hl_zip_type)).with_offset(offset))
             } else {
                 Ok(Self {
                     hl_dl_first,
@@ -494,20 +708,44 @@ pub mod dataxxx {
         }
     }
 
-    pub fn read_data_block(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Box<dyn VirtualBuf>, DynError> {
+    pub fn read_data_block(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Box<dyn VirtualBuf>, Mf4Error> {
+        read_data_block_with_cache(buf, offset, &new_dz_cache(DEFAULT_DZ_CACHE_SIZE))
+    }
+
+    pub fn read_data_block_with_cache(buf: &mut Cursor<&[u8]>, offset: u64, cache: &DzCache) -> Result<Box<dyn VirtualBuf>, Mf4Error> {
         if offset == 0 {
             return Ok(Box::new(DT::default()))   //  dg_data could be nil with empty data
         }
         let id: String = peek_block_type(buf, offset)?;
         match id.as_str() {
-            "DT" | "SD" => Ok(Box::new(DT::new(buf, offset)?)),
-            "DL" => Ok(Box::new(DataLink::new(buf, offset)?)),
+            "DT" | "SD" | "DV" | "DI" | "RD" | "RV" | "RI" => Ok(Box::new(DT::new(buf, offset)?)),
+            "DL" => Ok(Box::new(DataLink::new_with_cache(buf, offset, cache)?)),
+            "LD" => Ok(Box::new(DataLink::new_column(buf, offset, cache)?.0)),
             "HL" => {
                 let hl = HL::new(buf, offset)?;
-                Ok(Box::new(DataLink::new(buf, hl.hl_dl_first)?))
+                Ok(Box::new(DataLink::new_with_cache(buf, hl.hl_dl_first, cache)?))
             },
-            "DZ" => Ok(Box::new(DZBlock::new(buf, offset)?)),
-            _ => Err("Unknown data block id.".into()),
+            "DZ" => Ok(Box::new(DZBlock::new_with_cache(buf, offset, cache.clone())?)),
+            _ => Err(Mf4Error::corrupt_link(offset)),
+        }
+    }
+
+    pub fn read_column_blocks(buf: &mut Cursor<&[u8]>, offset: u64, cache: &DzCache) -> Result<DataAndInval, Mf4Error> {
+        /* data block of column oriented data group with its invalidation bits */
+        if peek_block_type(buf, offset)? == "LD" {
+            let (data, inval) = DataLink::new_column(buf, offset, cache)?;
+            Ok((Box::new(data), inval.map(|x| Box::new(x) as Box<dyn VirtualBuf>)))
+        } else {
+            Ok((read_data_block_with_cache(buf, offset, cache)?, None))
+        }
+    }
+
+    pub fn is_column_oriented(buf: &mut Cursor<&[u8]>, offset: u64) -> bool {
+        // MDF 4.2 column oriented storage uses LD list or DV block
+        match peek_block_type(buf, offset).unwrap_or_default().as_str() {
+            "LD" | "DV" => true,
+            "DZ" => DZBlock::new(buf, offset).is_ok_and(|dz| dz.get_org_block_type() == "DV"),
+            _ => false
         }
     }
-}
\ No newline at end of file
+}
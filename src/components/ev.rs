@@ -0,0 +1,201 @@
+pub mod event {
+    use std::io::Cursor;
+    use crate::block::BlockInfo;
+    use crate::parser::{get_clean_text, get_block_desc_by_name};
+    use crate::components::cn::channel::SyncType;
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum EventType {
+        Recording,
+        RecordingInterrupt,
+        AcquisitionInterrupt,
+        StartRecordingTrigger,
+        StopRecordingTrigger,
+        Trigger,
+        Marker,
+        Unknown(u8),
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum RangeType {
+        Point,
+        Begin,
+        End,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum EventCause {
+        Other,
+        Error,
+        Tool,
+        Script,
+        User,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Event {
+        offset: u64,  // block offset in file; referenced by parent and range links
+        name: String,
+        comment: String,
+        group_name: String,   // MDF 4.2 only
+        ev_type: EventType,
+        sync_type: SyncType,
+        range_type: RangeType,
+        cause: EventCause,
+        flags: u8,
+        parent: u64,
+        range: u64,   // for range end event, the corresponding range begin event
+        scope: Vec<u64>,   // CG or CN blocks this event applies to; empty for whole file
+        attachments: Vec<u64>,
+        creator_index: u16,
+        sync_base_value: i64,
+        sync_factor: f64,
+    }
+
+    impl Event {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let ev_desc = get_block_desc_by_name("EV".to_string()).ok_or("EV block not found")?;
+            let info: BlockInfo = ev_desc.try_parse_buf(buf, offset)?;
+            let name: String = get_clean_text(buf, info.get_link_offset_normal("ev_tx_name").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("ev_md_comment").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let group_name: String = get_clean_text(buf, info.get_link_offset_normal("ev_tx_group_name").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let ev_type: EventType = match info.get_data_value_first::<u8>("ev_type").ok_or("ev_type not found")? {
+                0 => EventType::Recording,
+                1 => EventType::RecordingInterrupt,
+                2 => EventType::AcquisitionInterrupt,
+                3 => EventType::StartRecordingTrigger,
+                4 => EventType::StopRecordingTrigger,
+                5 => EventType::Trigger,
+                6 => EventType::Marker,
+                other => EventType::Unknown(other),
+            };
+            let sync_type: SyncType = match info.get_data_value_first::<u8>("ev_sync_type").ok_or("ev_sync_type not found")? {
+                1 => SyncType::Time,
+                2 => SyncType::Angle,
+                3 => SyncType::Distance,
+                4 => SyncType::Index,
+                _ => SyncType::None,
+            };
+            let range_type: RangeType = match info.get_data_value_first::<u8>("ev_range_type").ok_or("ev_range_type not found")? {
+                1 => RangeType::Begin,
+                2 => RangeType::End,
+                _ => RangeType::Point,
+            };
+            let cause: EventCause = match info.get_data_value_first::<u8>("ev_cause").ok_or("ev_cause not found")? {
+                1 => EventCause::Error,
+                2 => EventCause::Tool,
+                3 => EventCause::Script,
+                4 => EventCause::User,
+                _ => EventCause::Other,
+            };
+            Ok(Self {
+                offset,
+                name,
+                comment,
+                group_name,
+                ev_type,
+                sync_type,
+                range_type,
+                cause,
+                flags: info.get_data_value_first("ev_flags").ok_or("ev_flags not found")?,
+                parent: info.get_link_offset_normal("ev_ev_parent").unwrap_or(0),
+                range: info.get_link_offset_normal("ev_ev_range").unwrap_or(0),
+                scope: info.get_link_offset_vec("ev_scope").unwrap_or_default(),
+                attachments: info.get_link_offset_vec("ev_at_reference").unwrap_or_default(),
+                creator_index: info.get_data_value_first("ev_creator_index").ok_or("ev_creator_index not found")?,
+                sync_base_value: info.get_data_value_first("ev_sync_base_value").ok_or("ev_sync_base_value not found")?,
+                sync_factor: info.get_data_value_first("ev_sync_factor").ok_or("ev_sync_factor not found")?,
+            })
+        }
+
+        pub fn get_offset(&self) -> u64 {
+            self.offset
+        }
+
+        pub fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        pub fn get_comment(&self) -> &str {
+            &self.comment
+        }
+
+        pub fn get_group_name(&self) -> &str {
+            &self.group_name
+        }
+
+        pub fn get_event_type(&self) -> &EventType {
+            &self.ev_type
+        }
+
+        pub fn get_sync_type(&self) -> &SyncType {
+            &self.sync_type
+        }
+
+        pub fn get_range_type(&self) -> &RangeType {
+            &self.range_type
+        }
+
+        pub fn get_cause(&self) -> &EventCause {
+            &self.cause
+        }
+
+        pub fn is_post_processing(&self) -> bool {
+            self.flags & 0x01 != 0
+        }
+
+        pub fn get_parent(&self) -> Option<u64> {
+            if self.parent != 0 { Some(self.parent) } else { None }
+        }
+
+        pub fn get_range(&self) -> Option<u64> {
+            if self.range != 0 { Some(self.range) } else { None }
+        }
+
+        pub fn get_scope(&self) -> &Vec<u64> {
+            &self.scope
+        }
+
+        pub fn get_attachment_refs(&self) -> &Vec<u64> {
+            &self.attachments
+        }
+
+        pub fn get_creator_index(&self) -> u16 {
+            self.creator_index
+        }
+
+        pub fn get_sync_base_value(&self) -> i64 {
+            self.sync_base_value
+        }
+
+        pub fn get_sync_factor(&self) -> f64 {
+            self.sync_factor
+        }
+
+        pub fn get_sync_value(&self) -> f64 {
+            // seconds, radians, meters or record index depending on sync type
+            self.sync_base_value as f64 * self.sync_factor
+        }
+
+        pub fn get_abs_time_ns(&self, start_time_ns: u64) -> Option<u64> {
+            /* absolute time (ns since epoch) for time synchronized events; start time comes from HD block */
+            match self.sync_type {
+                SyncType::Time => {
+                    // None if the event lies outside the u64 range, e.g. before the epoch
+                    let delta_ns: f64 = (self.get_sync_value() * 1e9).round();
+                    if !delta_ns.is_finite() || delta_ns.abs() >= i64::MAX as f64 {
+                        return None
+                    }
+                    let start_ns: i64 = i64::try_from(start_time_ns).ok()?;
+                    u64::try_from(start_ns.checked_add(delta_ns as i64)?).ok()
+                },
+                _ => None,
+            }
+        }
+    }
+}
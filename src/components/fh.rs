@@ -0,0 +1,96 @@
+pub mod filehistory {
+    use std::io::Cursor;
+    use chrono::{DateTime, FixedOffset};
+    use crate::block::BlockInfo;
+    use crate::parser::{get_clean_text, get_block_desc_by_name};
+    use crate::components::md::metadata::MdComment;
+    use crate::components::hd::header::to_date_time;
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone, Default)]
+    pub struct FileHistory {
+        time_ns: u64,   // UTC or local time depending on time flags
+        tz_offset_min: i16,
+        dst_offset_min: i16,
+        time_flags: u8,
+        comment: String,   // FHcomment xml
+        text: String,
+        tool_id: String,
+        tool_vendor: String,
+        tool_version: String,
+        user_name: String,
+    }
+
+    impl FileHistory {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let fh_desc = get_block_desc_by_name("FH".to_string()).ok_or("FH block not found")?;
+            let info: BlockInfo = fh_desc.try_parse_buf(buf, offset)?;
+            let comment: String = get_clean_text(buf, info.get_link_offset_normal("fh_md_comment").unwrap_or(0))
+                                    .unwrap_or("".to_string());
+            let md: MdComment = MdComment::parse(&comment);
+            let field = |name: &str| md.get_field(name).unwrap_or("").to_string();
+            Ok(Self {
+                time_ns: info.get_data_value_first("fh_time_ns").ok_or("fh_time_ns not found")?,
+                tz_offset_min: info.get_data_value_first("fh_tz_offset_min").ok_or("fh_tz_offset_min not found")?,
+                dst_offset_min: info.get_data_value_first("fh_dst_offset_min").ok_or("fh_dst_offset_min not found")?,
+                time_flags: info.get_data_value_first("fh_time_flags").ok_or("fh_time_flags not found")?,
+                text: md.get_text().trim().to_string(),
+                tool_id: field("tool_id"),
+                tool_vendor: field("tool_vendor"),
+                tool_version: field("tool_version"),
+                user_name: field("user_name"),
+                comment,
+            })
+        }
+
+        pub fn get_time_ns(&self) -> u64 {
+            self.time_ns
+        }
+
+        pub fn is_local_time(&self) -> bool {
+            self.time_flags & 0x01 != 0
+        }
+
+        pub fn has_tz_offset(&self) -> bool {
+            self.time_flags & 0x02 != 0
+        }
+
+        pub fn get_tz_offset_min(&self) -> i16 {
+            self.tz_offset_min
+        }
+
+        pub fn get_dst_offset_min(&self) -> i16 {
+            self.dst_offset_min
+        }
+
+        pub fn get_date_time(&self) -> Option<DateTime<FixedOffset>> {
+            /* local date time if the time zone is known, otherwise UTC */
+            to_date_time(self.time_ns, self.time_flags, self.tz_offset_min, self.dst_offset_min)
+        }
+
+        pub fn get_comment(&self) -> &str {
+            &self.comment
+        }
+
+        pub fn get_text(&self) -> &str {
+            &self.text
+        }
+
+        pub fn get_tool_id(&self) -> &str {
+            &self.tool_id
+        }
+
+        pub fn get_tool_vendor(&self) -> &str {
+            &self.tool_vendor
+        }
+
+        pub fn get_tool_version(&self) -> &str {
+            &self.tool_version
+        }
+
+        pub fn get_user_name(&self) -> &str {
+            &self.user_name
+        }
+    }
+}
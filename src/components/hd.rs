@@ -0,0 +1,129 @@
+pub mod header {
+    use chrono::{DateTime, FixedOffset};
+    use crate::block::BlockInfo;
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub enum TimeClass {
+        #[default]
+        LocalPcReferenceTime,
+        ExternalTimeSource,
+        ExternalAbsoluteSynchronized,
+        Unknown(u8),
+    }
+
+    impl From<u8> for TimeClass {
+        fn from(value: u8) -> Self {
+            match value {
+                0 => TimeClass::LocalPcReferenceTime,
+                10 => TimeClass::ExternalTimeSource,
+                16 => TimeClass::ExternalAbsoluteSynchronized,
+                other => TimeClass::Unknown(other),
+            }
+        }
+    }
+
+    pub(crate) fn to_date_time(time_ns: u64, time_flags: u8, tz_offset_min: i16, dst_offset_min: i16) -> Option<DateTime<FixedOffset>> {
+        /* local date time if the time zone is known, otherwise UTC; used by HD and FH blocks */
+        let offset_sec: i32 = if time_flags & 0x02 != 0 && time_flags & 0x01 == 0 {
+            (tz_offset_min as i32 + dst_offset_min as i32) * 60
+        } else {
+            0
+        };
+        let utc = DateTime::from_timestamp_nanos(time_ns as i64);
+        Some(utc.with_timezone(&FixedOffset::east_opt(offset_sec)?))
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Header {
+        program_id: String,  // id_prog of ID block
+        start_time_ns: u64,  // UTC or local time depending on time flags
+        tz_offset_min: i16,
+        dst_offset_min: i16,
+        time_flags: u8,
+        time_class: TimeClass,
+        flags: u8,
+        start_angle_rad: f64,
+        start_distance_m: f64,
+    }
+
+    impl Header {
+        pub fn new(info: &BlockInfo, program_id: String) -> Result<Self, Mf4Error> {
+            Ok(Self {
+                program_id,
+                start_time_ns: info.get_data_value_first("hd_start_time_ns").ok_or("hd_start_time_ns not found")?,
+                tz_offset_min: info.get_data_value_first("hd_tz_offset_min").ok_or("hd_tz_offset_min not found")?,
+                dst_offset_min: info.get_data_value_first("hd_dst_offset_min").ok_or("hd_dst_offset_min not found")?,
+                time_flags: info.get_data_value_first("hd_time_flags").ok_or("hd_time_flags not found")?,
+                time_class: info.get_data_value_first::<u8>("hd_time_class").ok_or("hd_time_class not found")?.into(),
+                flags: info.get_data_value_first("hd_flags").ok_or("hd_flags not found")?,
+                start_angle_rad: info.get_data_value_first("hd_start_angle_rad").ok_or("hd_start_angle_rad not found")?,
+                start_distance_m: info.get_data_value_first("hd_start_distance_m").ok_or("hd_start_distance_m not found")?,
+            })
+        }
+
+        pub fn new_v3(program_id: String, start_time_ns: u64, utc_offset_hour: Option<i16>, time_quality: u16) -> Self {
+            // MDF 3.x start time is converted to UTC; utc offset is only known since version 3.20
+            Self {
+                program_id,
+                start_time_ns,
+                tz_offset_min: utc_offset_hour.unwrap_or(0) * 60,
+                time_flags: if utc_offset_hour.is_some() { 0x02 } else { 0 },
+                time_class: (time_quality as u8).into(),
+                ..Default::default()
+            }
+        }
+
+        pub fn get_program_id(&self) -> &str {
+            &self.program_id
+        }
+
+        pub fn get_start_time_ns(&self) -> u64 {
+            self.start_time_ns
+        }
+
+        pub fn get_start_time_utc_ns(&self) -> Option<i64> {
+            /* start time in ns since epoch (UTC). None for a local start time, whose time zone is unknown
+               (offset fields are not valid then), or if the time can not be represented */
+            if self.is_local_time() {
+                return None
+            }
+            i64::try_from(self.start_time_ns).ok()
+        }
+
+        pub fn is_local_time(&self) -> bool {
+            self.time_flags & 0x01 != 0
+        }
+
+        pub fn has_tz_offset(&self) -> bool {
+            self.time_flags & 0x02 != 0
+        }
+
+        pub fn get_tz_offset_min(&self) -> i16 {
+            self.tz_offset_min
+        }
+
+        pub fn get_dst_offset_min(&self) -> i16 {
+            self.dst_offset_min
+        }
+
+        pub fn get_time_class(&self) -> &TimeClass {
+            &self.time_class
+        }
+
+        pub fn get_start_date_time(&self) -> Option<DateTime<FixedOffset>> {
+            to_date_time(self.start_time_ns, self.time_flags, self.tz_offset_min, self.dst_offset_min)
+        }
+
+        pub fn get_start_angle_rad(&self) -> Option<f64> {
+            // only valid if hd_flags bit 0 is set
+            (self.flags & 0x01 != 0).then_some(self.start_angle_rad)
+        }
+
+        pub fn get_start_distance_m(&self) -> Option<f64> {
+            // only valid if hd_flags bit 1 is set
+            (self.flags & 0x02 != 0).then_some(self.start_distance_m)
+        }
+    }
+}
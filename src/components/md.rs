@@ -0,0 +1,148 @@
+pub mod metadata {
+    use indexmap::IndexMap;
+    use roxmltree::{Document, Node};
+
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub enum CommentKind {
+        #[default]
+        Plain,  // TX block or text that is not xml
+        HD,
+        FH,
+        CG,
+        CN,
+        SI,
+        CC,
+        EV,
+        AT,
+        Other(String),
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct MdComment {
+        /* content of a MD block following the ASAM MDF xml schemas (HDcomment, CNcomment ...) */
+        kind: CommentKind,
+        text: String,  // <TX>
+        names: IndexMap<String, String>,  // <names>: name, display, vendor, description
+        fields: IndexMap<String, String>,  // other elements, e.g. time_source, raster, linker_name, path, bus, tool_id
+        properties: IndexMap<String, String>,  // <common_properties>; names of nested trees and lists are joined by '/'
+    }
+
+    fn join_name(prefix: &str, name: &str) -> String {
+        if prefix.is_empty() { name.to_string() } else { format!("{}/{}", prefix, name) }
+    }
+
+    fn node_text(node: &Node) -> String {
+        node.text().unwrap_or("").trim().to_string()
+    }
+
+    fn collect_properties(node: Node, prefix: &str, properties: &mut IndexMap<String, String>) {
+        for child in node.children().filter(|n| n.is_element()) {
+            let name: String = join_name(prefix, child.attribute("name").unwrap_or(child.tag_name().name()));
+            match child.tag_name().name() {
+                "e" => { properties.insert(name, node_text(&child)); },
+                "tree" => collect_properties(child, &name, properties),
+                "list" => child.children().filter(|n| n.is_element()).enumerate()
+                               .for_each(|(i, li)| collect_properties(li, &join_name(&name, &i.to_string()), properties)),
+                "elist" => child.children().filter(|n| n.is_element()).enumerate()
+                                .for_each(|(i, eli)| { properties.insert(join_name(&name, &i.to_string()), node_text(&eli)); }),
+                _ => {},
+            }
+        }
+    }
+
+    fn collect_fields(node: Node, prefix: &str, fields: &mut IndexMap<String, String>) {
+        // elements with children are flattened, e.g. "COMPU_METHOD/SHORT-NAME"
+        let name: String = join_name(prefix, node.tag_name().name());
+        if node.children().any(|n| n.is_element()) {
+            node.children().filter(|n| n.is_element()).for_each(|child| collect_fields(child, &name, fields));
+        } else {
+            fields.entry(name).or_insert(node_text(&node));
+        }
+    }
+
+    impl MdComment {
+        pub fn parse(raw: &str) -> Self {
+            let raw = raw.trim_end_matches('\0');
+            let doc = match Document::parse(raw) {
+                Ok(doc) if raw.trim_start().starts_with('<') => doc,
+                _ => return Self { text: raw.to_string(), ..Default::default() },
+            };
+            let root = doc.root_element();
+            let kind: CommentKind = match root.tag_name().name() {
+                "HDcomment" => CommentKind::HD,
+                "FHcomment" => CommentKind::FH,
+                "CGcomment" => CommentKind::CG,
+                "CNcomment" => CommentKind::CN,
+                "SIcomment" => CommentKind::SI,
+                "CCcomment" => CommentKind::CC,
+                "EVcomment" => CommentKind::EV,
+                "ATcomment" => CommentKind::AT,
+                other => CommentKind::Other(other.to_string()),
+            };
+            let mut comment = Self { kind, ..Default::default() };
+            for child in root.children().filter(|n| n.is_element()) {
+                match child.tag_name().name() {
+                    "TX" => comment.text = child.text().unwrap_or("").to_string(),
+                    "names" => child.children().filter(|n| n.is_element()).for_each(|name| {
+                        comment.names.entry(name.tag_name().name().to_string()).or_insert(node_text(&name));
+                    }),
+                    "common_properties" => collect_properties(child, "", &mut comment.properties),
+                    _ => collect_fields(child, "", &mut comment.fields),
+                }
+            }
+            comment
+        }
+
+        pub(crate) fn from_parts(kind: CommentKind, text: String, properties: IndexMap<String, String>) -> Self {
+            Self { kind, text, properties, ..Default::default() }
+        }
+
+        pub fn get_kind(&self) -> &CommentKind {
+            &self.kind
+        }
+
+        pub fn get_text(&self) -> &str {
+            &self.text
+        }
+
+        pub fn get_names(&self) -> &IndexMap<String, String> {
+            &self.names
+        }
+
+        pub fn get_fields(&self) -> &IndexMap<String, String> {
+            &self.fields
+        }
+
+        pub fn get_properties(&self) -> &IndexMap<String, String> {
+            &self.properties
+        }
+
+        pub fn get_field(&self, name: &str) -> Option<&str> {
+            self.fields.get(name).map(|x| x.as_str())
+        }
+
+        pub fn get_property(&self, name: &str) -> Option<&str> {
+            self.properties.get(name).map(|x| x.as_str())
+        }
+
+        pub fn get_display_name(&self) -> Option<&str> {
+            self.names.get("display").map(|x| x.as_str()).filter(|x| !x.is_empty())
+        }
+
+        pub fn get_author(&self) -> Option<&str> {
+            self.get_property("author")
+        }
+
+        pub fn get_department(&self) -> Option<&str> {
+            self.get_property("department")
+        }
+
+        pub fn get_project(&self) -> Option<&str> {
+            self.get_property("project")
+        }
+
+        pub fn get_subject(&self) -> Option<&str> {
+            self.get_property("subject")
+        }
+    }
+}
@@ -1,7 +1,10 @@
 pub mod sourceinfo {
+    use crate::error::Mf4Error;
     use std::io::Cursor;
+    use crate::components::md::metadata::MdComment;
     use crate::block::BlockInfo;
-    use crate::parser::{get_clean_text, get_block_desc_by_name};
+    use crate::parser::{get_clean_text, get_block_desc_by_name, get_block_desc_v3_by_name};
+    use crate::data_serde::{decode_text, DataValue};
     use std::fmt::Display;
 
     #[derive(Debug, Clone, Default)]
@@ -69,11 +72,11 @@ pub mod sourceinfo {
     }
 
     impl SourceInfo {
-        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<SourceInfo, Box<dyn std::error::Error>> {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<SourceInfo, Mf4Error> {
             if offset == 0 {
                 return Ok(Self::default()) // allows default
             }
-            let si_desc = get_block_desc_by_name("SI".to_string()).unwrap();
+            let si_desc = get_block_desc_by_name("SI".to_string()).ok_or("SI block not found")?;
             let info: BlockInfo = si_desc.try_parse_buf(buf, offset)?;
             let name_offset = info.get_link_offset_normal("si_tx_name")
                                                         .ok_or("Can not find source info name")?;
@@ -103,7 +106,7 @@ pub mod sourceinfo {
                 Some(8) => SiBusType::USB,
                 _ => SiBusType::OTHER,
             };
-            let flags:u8 = info.get_data_value_first("si_flags").unwrap();
+            let flags:u8 = info.get_data_value_first("si_flags").unwrap_or(0);
             let simulated = flags & 0x01 == 0x01;
             Ok(SourceInfo {
                 name,
@@ -115,6 +118,35 @@ pub mod sourceinfo {
             })
         }
 
+        pub fn new_v3(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<SourceInfo, Mf4Error> {
+            /* MDF 3.x channel extension (CE) block */
+            if offset == 0 {
+                return Ok(Self::default()) // allows default
+            }
+            let ce_desc = get_block_desc_v3_by_name("CE".to_string()).ok_or("CE block not found")?;
+            let info: BlockInfo = ce_desc.try_parse_buf_v3(buf, offset)?;
+            let ce_type: u16 = info.get_data_value_first("ce_type").ok_or("ce_type not found")?;
+            let ext: Vec<u8> = info.get_data_value_copy("unparsed_data").unwrap_or(DataValue::BYTE(Vec::new())).try_into()?;
+            let text = |start: usize, end: usize| -> String {
+                ext.get(start..end).map(|b| decode_text(b).trim_end_matches('\0').to_string()).unwrap_or_default()
+            };
+            match ce_type {
+                2 => Ok(SourceInfo {   // DIM: module number, address, description, ECU identification
+                    name: text(86, 118),
+                    path: text(6, 86),
+                    si_type: SiType::ECU,
+                    ..Default::default()
+                }),
+                19 => Ok(SourceInfo {  // Vector CAN: CAN id, CAN channel, message name, sender name
+                    name: text(8, 44),
+                    path: text(44, 80),
+                    si_type: SiType::BUS,
+                    bus_type: SiBusType::CAN,
+                    ..Default::default()
+                }),
+                _ => Ok(Self::default())
+            }
+        }
 
         pub fn get_name(&self) -> &str {
             &self.name
@@ -128,6 +160,11 @@ pub mod sourceinfo {
             &self.comment
         }
 
+        pub fn get_md_comment(&self) -> MdComment {
+            // comment parsed from MD block xml
+            MdComment::parse(&self.comment)
+        }
+
         pub fn get_si_type(&self) -> &SiType {
             &self.si_type
         }
@@ -0,0 +1,123 @@
+pub mod samplereduction {
+    use std::io::Cursor;
+    use crate::block::BlockInfo;
+    use crate::parser::{get_block_desc_by_name, peek_block_type};
+    use crate::components::cn::channel::{Channel, SyncType};
+    use crate::components::cg::channelgroup::ChannelGroup;
+    use crate::components::dx::dataxxx::{read_data_block_with_cache, read_column_blocks, DZBlock, DzCache};
+
+    use crate::error::Mf4Error;
+
+    #[derive(Debug, Clone)]
+    pub struct SampleReduction {
+        offset: u64,
+        data: u64,   // sr_data; RD, RV or a list of them
+        cycle_count: u64,
+        interval: f64,
+        sync_type: SyncType,
+        flags: u8,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct ReducedSeries {
+        /* one reduced sample per interval */
+        pub interval: f64,
+        pub master: Vec<f64>,  // mean value of master channel, or interval index * interval if there is none
+        pub mean: Vec<f64>,
+        pub min: Vec<f64>,
+        pub max: Vec<f64>,
+    }
+
+    impl SampleReduction {
+        pub fn new(buf: &mut Cursor<&[u8]>, offset: u64) -> Result<Self, Mf4Error> {
+            let sr_desc = get_block_desc_by_name("SR".to_string()).ok_or("SR block not found")?;
+            let info: BlockInfo = sr_desc.try_parse_buf(buf, offset)?;
+            let data: u64 = info.get_link_offset_normal("sr_data").unwrap_or(0);
+            let cycle_count: u64 = info.get_data_value_first("sr_cycle_count").ok_or("sr_cycle_count not found")?;
+            let interval: f64 = info.get_data_value_first("sr_interval").ok_or("sr_interval not found")?;
+            let sync_type: SyncType = match info.get_data_value_first::<u8>("sr_sync_type") {
+                Some(1) => SyncType::Time,
+                Some(2) => SyncType::Angle,
+                Some(3) => SyncType::Distance,
+                Some(4) => SyncType::Index,
+                _ => return Err("Invalid sr_sync_type".into()),
+            };
+            let flags: u8 = info.get_data_value_first("sr_flags").ok_or("sr_flags not found")?;
+            Ok(Self {
+                offset,
+                data,
+                cycle_count,
+                interval,
+                sync_type,
+                flags,
+            })
+        }
+
+        pub fn get_offset(&self) -> u64 {
+            self.offset
+        }
+
+        pub fn get_cycle_count(&self) -> u64 {
+            self.cycle_count
+        }
+
+        pub fn get_interval(&self) -> f64 {
+            self.interval
+        }
+
+        pub fn get_sync_type(&self) -> &SyncType {
+            &self.sync_type
+        }
+
+        pub fn has_inval_bytes(&self) -> bool {
+            self.flags & 0x01 != 0
+        }
+
+        fn inline_inval_bytes(&self, buf: &mut Cursor<&[u8]>, cg: &ChannelGroup) -> u32 {
+            // RD records carry invalidation bytes; RV blocks (MDF 4.2) keep them in separate RI blocks
+            if !self.has_inval_bytes() {
+                return 0
+            }
+            let is_rv = match peek_block_type(buf, self.data).unwrap_or_default().as_str() {
+                "RV" | "LD" => true,
+                "DZ" => DZBlock::new(buf, self.data).is_ok_and(|dz| dz.get_org_block_type() == "RV"),
+                _ => false,
+            };
+            if is_rv { 0 } else { cg.get_invalid_bytes() }
+        }
+
+        pub fn get_reduced_values(&self, buf: &mut Cursor<&[u8]>, cg: &ChannelGroup, cn: &Channel, cache: &DzCache) -> Result<ReducedSeries, Mf4Error> {
+            /* each reduced record holds three records of the channel group without record id: mean, min and max;
+               in RD blocks each of them is followed by the invalidation bytes if sr_flags bit 0 is set */
+            if self.data == 0 || self.cycle_count == 0 {
+                return Ok(ReducedSeries { interval: self.interval, ..Default::default() })
+            }
+            let sub_len: u64 = (cg.get_data_bytes() + self.inline_inval_bytes(buf, cg)) as u64;
+            let data = if peek_block_type(buf, self.data)? == "LD" {
+                read_column_blocks(buf, self.data, cache)?.0
+            } else {
+                read_data_block_with_cache(buf, self.data, cache)?
+            };
+            if data.get_data_len() < sub_len * 3 * self.cycle_count {
+                return Err("Reduced data block is shorter than expected".into());
+            }
+            let mut record: Vec<u8> = vec![0u8; (sub_len * 3) as usize];
+            let mut series = ReducedSeries {
+                interval: self.interval,
+                ..Default::default()
+            };
+            for i in 0..self.cycle_count {
+                data.read_virtual_buf(buf, i * sub_len * 3, &mut record)?;
+                let mut values = record.chunks_exact(sub_len as usize);
+                series.mean.push(cn.get_physical_value(values.next().ok_or("Invalid reduced record")?)?);
+                series.min.push(cn.get_physical_value(values.next().ok_or("Invalid reduced record")?)?);
+                series.max.push(cn.get_physical_value(values.next().ok_or("Invalid reduced record")?)?);
+                series.master.push(match cg.get_master() {
+                    Some(master) => master.get_physical_value(&record[..sub_len as usize])?,
+                    None => i as f64 * self.interval,
+                });
+            }
+            Ok(series)
+        }
+    }
+}
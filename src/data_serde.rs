@@ -1,6 +1,7 @@
 use byteorder::{ByteOrder, LittleEndian, BigEndian};
 use half::f16;
 use indexmap::IndexMap;
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
 
 pub struct UTF16String {
     pub inner: String,
@@ -84,23 +85,24 @@ impl FromBeBytes for f64 {
 
 impl FromBeBytes for String {
     fn from_be_bytes(buf: &[u8]) -> Self{
-        let mut new_arr: Vec<u8> = buf.to_vec();    // has to copy because we are going to reverse it
-        reverse_bytes_array(&mut new_arr);
-        String::from_utf8(new_arr).unwrap_or("ERROR during parsing BE STRING".to_string())
+        decode_text(buf)   // single byte encodings have no byte order
     }
 }
 
 impl FromBeBytes for UTF16String {
     fn from_be_bytes(buf: &[u8]) -> Self{
-        let mut new_arr: Vec<u8> = buf.to_vec();
-        reverse_bytes_array(&mut new_arr);
-        let u16s = from_u8_vec(&new_arr).unwrap();
         UTF16String {
-            inner: String::from_utf16_lossy(&u16s[..])
+            inner: decode_utf16(buf, true)
         }
     }
 }
 
+impl FromBeBytes for Vec<u8> {
+    fn from_be_bytes(buf: &[u8]) -> Self{
+        buf.to_vec()
+    }
+}
+
 /* Little Endian */
 impl FromLeBytes for u8 {
     fn from_le_bytes(buf: &[u8]) -> Self {
@@ -170,17 +172,69 @@ impl FromLeBytes for f64 {
 
 impl FromLeBytes for String {
     fn from_le_bytes(buf: &[u8]) -> Self{
-        String::from_utf8(buf.to_vec())
-            .unwrap_or("ERROR during parsing LB STRING".to_string())
+        decode_text(buf)
     }
 }
 
 impl FromLeBytes for UTF16String {
     fn from_le_bytes(buf: &[u8]) -> Self{
-        let new_arr: Vec<u8> = buf.to_vec();
-        let u16s = from_u8_vec(&new_arr).unwrap();
         UTF16String {
-            inner: String::from_utf16_lossy(&u16s[..])
+            inner: decode_utf16(buf, false)
+        }
+    }
+}
+
+impl FromLeBytes for Vec<u8> {
+    fn from_le_bytes(buf: &[u8]) -> Self{
+        buf.to_vec()
+    }
+}
+
+const UTF8_BOM: [u8; 3] = [0xef, 0xbb, 0xbf];
+
+pub fn decode_latin1(buf: &[u8]) -> String {
+    // ISO-8859-1 maps each byte to the unicode code point of the same value
+    buf.iter().map(|b| *b as char).collect()
+}
+
+pub fn decode_text(buf: &[u8]) -> String {
+    /* text of TX/MD blocks and MDF 3 strings: UTF-8 (BOM is skipped); 
+       falls back to ISO-8859-1 if the bytes are no valid UTF-8 */
+    let buf: &[u8] = buf.strip_prefix(&UTF8_BOM[..]).unwrap_or(buf);
+    match std::str::from_utf8(buf) {
+        Ok(s) => s.to_string(),
+        Err(_) => decode_latin1(buf),
+    }
+}
+
+pub fn decode_utf16(buf: &[u8], big_endian: bool) -> String {
+    /* byte order mark overrides the byte order of the data type; an odd trailing byte is ignored */
+    let (buf, big_endian) = match buf {
+        [0xff, 0xfe, rest @ ..] => (rest, false),
+        [0xfe, 0xff, rest @ ..] => (rest, true),
+        _ => (buf, big_endian),
+    };
+    let units: Vec<u16> = buf.chunks_exact(2)
+                             .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+                             .take_while(|u| *u != 0)
+                             .collect();
+    String::from_utf16_lossy(&units)
+}
+
+pub fn decode_string(buf: &[u8], data_type: u8) -> String {
+    /* string channel value by cn_data_type: 6 ISO-8859-1, 7 UTF-8, 8 UTF-16 LE, 9 UTF-16 BE;
+       value ends at the first zero terminator. A UTF-8 BOM is respected for both single byte types */
+    match data_type {
+        8 => decode_utf16(buf, false),
+        9 => decode_utf16(buf, true),
+        _ => {
+            let end: usize = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+            let buf: &[u8] = &buf[..end];
+            match buf.strip_prefix(&UTF8_BOM[..]) {
+                Some(rest) => String::from_utf8_lossy(rest).to_string(),
+                None if data_type == 6 => decode_latin1(buf),
+                None => String::from_utf8_lossy(buf).to_string(),
+            }
         }
     }
 }
@@ -224,6 +278,39 @@ pub fn right_shift_bytes(bytes: &Vec<u8>, shift: u8) -> Result<Vec<u8>, &str> {
     Ok(new)
 }
 
+pub fn extract_bits(bytes: &[u8], bit_offset: u8, bit_count: u32, big_endian: bool, signed: bool) -> Result<Vec<u8>, &'static str> {
+    /* integer bit field: the bytes spanned by bit offset + bit count are read in the given byte order,
+       shifted right by bit offset and masked by bit count (same rule for Intel and Motorola order).
+       Result is sign extended and padded to 1, 2, 4 or 8 bytes in the same byte order */
+    let span: usize = (bit_offset as u32 + bit_count).div_ceil(8) as usize;
+    if bit_count == 0 || span > 8 || bytes.len() < span {
+        return Err("Bit field must fit into 8 bytes");
+    }
+    let raw: u64 = if big_endian {
+        bytes[..span].iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+    } else {
+        bytes[..span].iter().rev().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+    };
+    let mut value: u64 = raw >> bit_offset;
+    if bit_count < 64 {
+        value &= (1u64 << bit_count) - 1;
+        if signed && (value >> (bit_count - 1)) & 1 == 1 {
+            value |= u64::MAX << bit_count;
+        }
+    }
+    let width: usize = match bit_count {
+        0..=8 => 1,
+        9..=16 => 2,
+        17..=32 => 4,
+        _ => 8,
+    };
+    if big_endian {
+        Ok(value.to_be_bytes()[8 - width..].to_vec())
+    } else {
+        Ok(value.to_le_bytes()[..width].to_vec())
+    }
+}
+
 pub fn bytes_and_bits(bytes: &mut Vec<u8>, bits: u32) {
     // modify in place; this operation can not fail
     let num_of_bytes = (bits as f32 / 8.0).floor() as usize;
@@ -244,15 +331,50 @@ pub fn reverse_bytes_array(arr: &mut [u8]) {
     }
 }
 
-fn from_u8_vec(bytes: &Vec<u8>) -> Result<Vec<u16>, &'static str> {
-    if bytes.len() % 2 != 0 {
-        return Err("Length of bytes must be even");
-    }
-    let result: Vec<u16> = bytes.chunks(2)
-                                .map(|chunk| u16::from_le_bytes(chunk.try_into().unwrap()))
-                                .collect();
-    Ok(result)
+pub fn parse_canopen_date(bytes: &[u8]) -> Result<NaiveDateTime, &'static str> {
+    /* CANopen date: ms (u16), minute, hour, day, month, year since 2000; upper bits are flags or week day */
+    if bytes.len() < 7 {
+        return Err("CANopen date needs 7 bytes");
+    }
+    let ms: u16 = u16::from_le_bytes([bytes[0], bytes[1]]);
+    let minute: u32 = (bytes[2] & 0x3f) as u32;
+    let hour: u32 = (bytes[3] & 0x1f) as u32;
+    let day: u32 = (bytes[4] & 0x1f) as u32;
+    let month: u32 = (bytes[5] & 0x3f) as u32;
+    let year: i32 = 2000 + (bytes[6] & 0x7f) as i32;
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|d| d.and_hms_milli_opt(hour, minute, (ms / 1000) as u32, (ms % 1000) as u32))
+        .ok_or("Invalid CANopen date")
+}
+
+pub fn parse_canopen_time(bytes: &[u8]) -> Result<NaiveDateTime, &'static str> {
+    /* CANopen time: ms since midnight (28 bits) and days since 1984-01-01 */
+    if bytes.len() < 6 {
+        return Err("CANopen time needs 6 bytes");
+    }
+    let ms: u32 = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) & 0x0fff_ffff;
+    let days: u16 = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let epoch: NaiveDateTime = NaiveDate::from_ymd_opt(1984, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    Ok(epoch + TimeDelta::days(days as i64) + TimeDelta::milliseconds(ms as i64))
+}
+
+pub fn parse_complex(bytes: &[u8], big_endian: bool) -> Result<(f64, f64), &'static str> {
+    /* real part followed by imaginary part, both parts are float16, float32 or float64 */
+    let (re, im) = bytes.split_at(bytes.len() / 2);
+    let part = |b: &[u8]| -> Result<f64, &'static str> {
+        match (b.len(), big_endian) {
+            (2, false) => Ok(parse_le_value::<f16>(b).to_f64()),
+            (2, true) => Ok(parse_be_value::<f16>(b).to_f64()),
+            (4, false) => Ok(parse_le_value::<f32>(b) as f64),
+            (4, true) => Ok(parse_be_value::<f32>(b) as f64),
+            (8, false) => Ok(parse_le_value::<f64>(b)),
+            (8, true) => Ok(parse_be_value::<f64>(b)),
+            _ => Err("Invalid bit size for complex number"),
+        }
+    };
+    Ok((part(re)?, part(im)?))
 }
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum StringOrReal {  // for Value2Text and ValueRange2Text conversions only
     String(String),
@@ -287,7 +409,10 @@ pub enum DataValue {
     FLOAT16(Vec<f16>),
     STRUCT(IndexMap<String, DataValue>),   
     BYTEARRAY(Vec<Vec<u8>>),
-    MIXED(Vec<StringOrReal>)
+    MIXED(Vec<StringOrReal>),
+    MIME(String, Vec<Vec<u8>>),   // MIME type and payload of each sample
+    DATETIME(Vec<NaiveDateTime>),   // CANopen date/time; no time zone information
+    COMPLEX(Vec<(f64, f64)>),   // real and imaginary part
 }
 
 
@@ -295,7 +420,8 @@ pub enum DataValue {
 impl DataValue {
     pub fn is_num(&self) -> bool {
         match self {
-            &DataValue::CHAR(_) | &DataValue::STRINGS(_) | &DataValue::BYTEARRAY(_) | &DataValue::STRUCT(_) => false,
+            &DataValue::CHAR(_) | &DataValue::STRINGS(_) | &DataValue::BYTEARRAY(_) | &DataValue::STRUCT(_) |
+            &DataValue::MIME(_, _) | &DataValue::DATETIME(_) | &DataValue::COMPLEX(_) => false,
             _ => true
         }
     }
@@ -434,6 +560,26 @@ impl TryFrom<DataValue> for Vec<f16> {
     }
 }
 
+impl TryFrom<DataValue> for Vec<NaiveDateTime> {
+    type Error = &'static str;
+    fn try_from(value: DataValue) -> Result<Self, Self::Error> {
+        match value {
+            DataValue::DATETIME(s) => Ok(s),
+            _ => Err("DataValue is not a date time")
+        }
+    }
+}
+
+impl TryFrom<DataValue> for Vec<(f64, f64)> {
+    type Error = &'static str;
+    fn try_from(value: DataValue) -> Result<Self, Self::Error> {
+        match value {
+            DataValue::COMPLEX(s) => Ok(s),
+            _ => Err("DataValue is not a complex")
+        }
+    }
+}
+
 impl TryFrom<DataValue> for Vec<String> {
     type Error = &'static str;
     fn try_from(value: DataValue) -> Result<Self, Self::Error> {
@@ -492,6 +638,69 @@ pub mod serde_tests {
         assert_eq!(vec![64, 96, 128, 0], new);
     }
 
+    #[rstest]
+    fn test_canopen_date_time() {
+        // 2024-02-29 13:45:30.250, summer time flag and week day bits set
+        let date = [0x2a, 0x76, 0x2d, 0x8d, 0x9d, 0x02, 0x18];
+        assert_eq!(parse_canopen_date(&date).unwrap().to_string(), "2024-02-29 13:45:30.250");
+        assert!(parse_canopen_date(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x0d, 0x18]).is_err());
+        // 1984-01-02 00:00:01.500
+        let time = [0xdc, 0x05, 0x00, 0x00, 0x01, 0x00];
+        assert_eq!(parse_canopen_time(&time).unwrap().to_string(), "1984-01-02 00:00:01.500");
+    }
+
+    #[rstest]
+    fn test_complex_from_bytes() {
+        let mut le: Vec<u8> = 1.5f32.to_le_bytes().to_vec();
+        le.extend_from_slice(&(-2.0f32).to_le_bytes());
+        assert_eq!(parse_complex(&le, false).unwrap(), (1.5, -2.0));
+        let mut be: Vec<u8> = 0.25f64.to_be_bytes().to_vec();
+        be.extend_from_slice(&4.0f64.to_be_bytes());
+        assert_eq!(parse_complex(&be, true).unwrap(), (0.25, 4.0));
+        assert_eq!(parse_complex(&[0x00, 0x3c, 0x00, 0xc0], false).unwrap(), (1.0, -2.0));
+        assert!(parse_complex(&[0u8; 6], false).is_err());
+    }
+
+    #[rstest]
+    fn test_string_decoding() {
+        assert_eq!(decode_string(b"Gr\xfc\xdfe\0\0", 6), "Grüße");
+        assert_eq!(decode_string("Grüße\0xx".as_bytes(), 7), "Grüße");
+        assert_eq!(decode_string(b"\xef\xbb\xbf\xc3\xa4", 6), "ä");   // UTF-8 BOM wins over ISO-8859-1
+        assert_eq!(decode_string(b"\xef\xbb\xbfok", 7), "ok");
+        assert_eq!(decode_string(&[0x41, 0x00, 0xe4, 0x00, 0x00, 0x00, 0x42, 0x00], 8), "Aä");
+        assert_eq!(decode_string(&[0x00, 0x41, 0x00, 0xe4], 9), "Aä");
+        assert_eq!(decode_string(&[0xff, 0xfe, 0x41, 0x00, 0x42, 0x00], 9), "AB");   // BOM overrides data type
+        assert_eq!(decode_string(&[0xfe, 0xff, 0x00, 0x41, 0x00, 0x42], 8), "AB");
+        assert_eq!(parse_be_value::<UTF16String>(&[0x00, 0x41, 0x00, 0x42]).inner, "AB");
+        assert_eq!(decode_text(b"Motordrehzahl \xd6l"), "Motordrehzahl Öl");
+        assert_eq!(decode_text("\u{feff}Öl".as_bytes()), "Öl");
+    }
+
+    #[rstest]
+    #[case(&[0x12, 0x34], 4, 12, 0x123)]
+    #[case(&[0x12, 0x34], 3, 10, 0x246)]
+    #[case(&[0x12, 0x34, 0x56], 6, 12, 0x8d1)]
+    #[case(&[0x12, 0x34, 0x56], 0, 24, 0x123456)]
+    #[case(&[0xa5], 2, 3, 0x01)]
+    #[case(&[0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef], 4, 60, 0x0123456789abcde)]
+    fn test_extract_bits_motorola(#[case] bytes: &[u8], #[case] bit_offset: u8, #[case] bit_count: u32, #[case] expected: u64) {
+        let value: Vec<u8> = extract_bits(bytes, bit_offset, bit_count, true, false).unwrap();
+        let mut padded: [u8; 8] = [0u8; 8];
+        padded[8 - value.len()..].copy_from_slice(&value);
+        assert_eq!(u64::from_be_bytes(padded), expected);
+    }
+
+    #[rstest]
+    fn test_extract_bits() {
+        assert_eq!(extract_bits(&[0x12, 0x34], 4, 12, false, false).unwrap(), vec![0x41, 0x03]);   // Intel: 0x3412 >> 4
+        assert_eq!(parse_be_value::<i16>(&extract_bits(&[0xff, 0xf0], 4, 12, true, true).unwrap()), -1);
+        assert_eq!(parse_be_value::<i16>(&extract_bits(&[0x80, 0x00], 4, 12, true, true).unwrap()), -2048);
+        assert_eq!(parse_be_value::<i16>(&extract_bits(&[0x7f, 0xf0], 4, 12, true, true).unwrap()), 2047);
+        assert_eq!(parse_le_value::<i8>(&extract_bits(&[0b0011_1000], 3, 3, false, true).unwrap()), -1);
+        assert_eq!(parse_be_value::<u32>(&extract_bits(&[0x01, 0x02, 0x03], 0, 24, true, false).unwrap()), 0x010203);
+        assert!(extract_bits(&[0u8; 9], 4, 64, true, false).is_err());
+    }
+
     #[rstest]
     fn test_bytes_fn() {
         let mut a: Vec<u8> = vec![0x01u8, 0x02, 0xff, 0xff];
@@ -0,0 +1,140 @@
+use std::fmt::Display;
+
+/*
+Errors of the parser. Offset is the absolute file offset of the block which could not be read, if known.
+Errors raised deep inside a block get the offset of the block attached by the block constructors.
+*/
+#[derive(Debug)]
+pub enum Mf4Error {
+    Io { source: std::io::Error, offset: Option<u64> },
+    BadMagic { found: String },                         // file does not start with "MDF     "
+    UnsupportedVersion { version: String },
+    UnknownBlock { id: String, offset: u64 },           // block id without block description
+    CorruptLink { target: u64, offset: Option<u64> },   // link does not point to a valid block or list is cyclic
+    Unsupported { feature: String, offset: Option<u64> },
+    Conversion { reason: String, offset: Option<u64> },
+    Malformed { reason: String, offset: Option<u64> },  // block content is inconsistent
+}
+
+impl Mf4Error {
+    pub fn unsupported(feature: impl Into<String>) -> Self {
+        Mf4Error::Unsupported { feature: feature.into(), offset: None }
+    }
+
+    pub fn conversion(reason: impl Into<String>) -> Self {
+        Mf4Error::Conversion { reason: reason.into(), offset: None }
+    }
+
+    pub fn corrupt_link(target: u64) -> Self {
+        Mf4Error::CorruptLink { target, offset: None }
+    }
+
+    pub fn with_offset(self, block_offset: u64) -> Self {
+        // attach the offset of the enclosing block; an offset which is already known is kept
+        match self {
+            Mf4Error::Io { source, offset } => Mf4Error::Io { source, offset: offset.or(Some(block_offset)) },
+            Mf4Error::CorruptLink { target, offset } => Mf4Error::CorruptLink { target, offset: offset.or(Some(block_offset)) },
+            Mf4Error::Unsupported { feature, offset } => Mf4Error::Unsupported { feature, offset: offset.or(Some(block_offset)) },
+            Mf4Error::Conversion { reason, offset } => Mf4Error::Conversion { reason, offset: offset.or(Some(block_offset)) },
+            Mf4Error::Malformed { reason, offset } => Mf4Error::Malformed { reason, offset: offset.or(Some(block_offset)) },
+            other => other,
+        }
+    }
+
+    pub fn get_offset(&self) -> Option<u64> {
+        match self {
+            Mf4Error::Io { offset, .. } | Mf4Error::CorruptLink { offset, .. } | Mf4Error::Unsupported { offset, .. } |
+            Mf4Error::Conversion { offset, .. } | Mf4Error::Malformed { offset, .. } => *offset,
+            Mf4Error::UnknownBlock { offset, .. } => Some(*offset),
+            Mf4Error::BadMagic { .. } | Mf4Error::UnsupportedVersion { .. } => None,
+        }
+    }
+}
+
+impl Display for Mf4Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mf4Error::Io { source, .. } => write!(f, "I/O error: {}", source)?,
+            Mf4Error::BadMagic { found } => write!(f, "Not a mdf file; file identifier is {:?}", found)?,
+            Mf4Error::UnsupportedVersion { version } => write!(f, "Unsupported mdf version: {}", version)?,
+            Mf4Error::UnknownBlock { id, .. } => write!(f, "Unknown block id {:?}", id)?,
+            Mf4Error::CorruptLink { target, .. } => write!(f, "Corrupt link to offset {}", target)?,
+            Mf4Error::Unsupported { feature, .. } => write!(f, "Not supported: {}", feature)?,
+            Mf4Error::Conversion { reason, .. } => write!(f, "Conversion failed: {}", reason)?,
+            Mf4Error::Malformed { reason, .. } => write!(f, "{}", reason)?,
+        }
+        match self.get_offset() {
+            Some(offset) => write!(f, " (at offset {})", offset),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Mf4Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Mf4Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Mf4Error {
+    fn from(source: std::io::Error) -> Self {
+        Mf4Error::Io { source, offset: None }
+    }
+}
+
+impl From<&str> for Mf4Error {
+    fn from(reason: &str) -> Self {
+        Mf4Error::Malformed { reason: reason.to_string(), offset: None }
+    }
+}
+
+impl From<String> for Mf4Error {
+    fn from(reason: String) -> Self {
+        Mf4Error::Malformed { reason, offset: None }
+    }
+}
+
+impl From<std::string::FromUtf8Error> for Mf4Error {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
+
+impl From<std::str::Utf8Error> for Mf4Error {
+    fn from(e: std::str::Utf8Error) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
+
+impl From<std::array::TryFromSliceError> for Mf4Error {
+    fn from(e: std::array::TryFromSliceError) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
+
+impl From<std::num::TryFromIntError> for Mf4Error {
+    fn from(e: std::num::TryFromIntError) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
+
+impl From<toml::de::Error> for Mf4Error {
+    fn from(e: toml::de::Error) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
+
+impl From<evalexpr::EvalexprError> for Mf4Error {
+    fn from(e: evalexpr::EvalexprError) -> Self {
+        Mf4Error::conversion(e.to_string())
+    }
+}
+
+impl From<chrono::ParseError> for Mf4Error {
+    fn from(e: chrono::ParseError) -> Self {
+        Mf4Error::from(e.to_string())
+    }
+}
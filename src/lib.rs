@@ -1,9 +1,11 @@
 pub mod components;
 pub mod data_serde;
+pub mod error;
 
 
 pub use crate::parser::Mf4Wrapper;
 pub use crate::data_serde::DataValue;
+pub use crate::error::Mf4Error;
 pub use crate::components::dg::datagroup::ChannelLink;
 
 use wasm_bindgen::prelude::*;
@@ -34,7 +36,8 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
     use indexmap::IndexMap;
     use std::convert::{TryInto, TryFrom};
     use crate::parser::get_block_desc_by_name;
-    use crate::data_serde::DataValue;
+    use crate::data_serde::{decode_text, DataValue};
+    use crate::error::Mf4Error;
     
 
     #[derive(Serialize, Deserialize, Debug)]
@@ -58,8 +61,19 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
             }
         }
 
-        pub fn try_parse_value(&self, cur: &mut Cursor<&Vec<u8>>) -> Result<DataValue, Box<dyn std::error::Error>> {
-            let size:usize = self.size.unwrap_or(1).try_into().unwrap();  // convert to usize for later convience
+        pub fn get_byte_len(&self) -> Option<usize> {
+            // None if size is variable
+            let width: usize = match self.data_type {
+                DataType::CHAR | DataType::BYTE | DataType::UINT8 => 1,
+                DataType::INT16 | DataType::UINT16 => 2,
+                DataType::INT32 | DataType::UINT32 => 4,
+                DataType::UINT64 | DataType::INT64 | DataType::REAL => 8,
+            };
+            Some(self.size? as usize * width)
+        }
+
+        pub fn try_parse_value(&self, cur: &mut Cursor<&Vec<u8>>) -> Result<DataValue, Mf4Error> {
+            let size:usize = self.size.unwrap_or(1).try_into()?;  // convert to usize for later convience
             match self.data_type {
                 DataType::CHAR => {
                     let size:usize;
@@ -70,7 +84,7 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                     }
                     let mut byte_buf: Vec<u8> = vec![0u8;size];
                     cur.read_exact(&mut byte_buf)?;
-                    Ok(DataValue::CHAR(String::from_utf8(byte_buf)?))   // might be wrong, asam manual says that CHAR data is encoded in ISO-8859-1
+                    Ok(DataValue::CHAR(decode_text(&byte_buf)))   // UTF-8 for mdf4; MDF 3 text is usually ISO-8859-1
                 },
                 DataType::UINT8 => {
                     let mut byte_buf: Vec<u8> = vec![0u8;size];
@@ -91,64 +105,64 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                         size = self.size.unwrap() as usize;
                     }
                     let mut eight_bytes_buf: [u8; 8] = [0u8;8];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut eight_bytes_buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut eight_bytes_buf)?;
                         res.push(LittleEndian::read_u64(&eight_bytes_buf));
-                    });
+                    }
                     Ok(DataValue::UINT64(res))
                 },
                 DataType::INT16 => {
                     let mut res: Vec<i16> = Vec::new();
                     let mut two_byte_buf: [u8; 2] = [0u8;2];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut two_byte_buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut two_byte_buf)?;
                         res.push(LittleEndian::read_i16(&two_byte_buf));
-                    });
+                    }
                     Ok(DataValue::INT16(res))
                 },
                 DataType::UINT16 => {
                     let mut res: Vec<u16> = Vec::new();
                     let mut two_byte_buf = [0u8;2];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut two_byte_buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut two_byte_buf)?;
                         res.push(LittleEndian::read_u16(&two_byte_buf));
-                    });
+                    }
                     Ok(DataValue::UINT16(res))
                 },
                 DataType::INT32 => {
                     let mut res: Vec<i32> = Vec::new();
                     let mut buf: [u8; 4] = [0u8;4];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut buf)?;
                         res.push(LittleEndian::read_i32(&buf));
-                    });
+                    }
                     Ok(DataValue::INT32(res))
                 },
                 DataType::UINT32 => {
                     let mut res: Vec<u32> = Vec::new();
                     let mut buf: [u8; 4] = [0u8;4];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut buf)?;
                         res.push(LittleEndian::read_u32(&buf));
-                    });
+                    }
                     Ok(DataValue::UINT32(res))
                 },
                 DataType::INT64 => {
                     let mut res: Vec<i64> = Vec::new();
                     let mut buf: [u8; 8] = [0u8;8];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut buf)?;
                         res.push(LittleEndian::read_i64(&buf));
-                    });
+                    }
                     Ok(DataValue::INT64(res))
                 },
                 DataType::REAL => {
                     let mut res: Vec<f64> = Vec::new();
                     let mut buf: [u8; 8] = [0u8;8];
-                    (0..size).into_iter().for_each(|_| {
-                        cur.read_exact(&mut buf).unwrap();
+                    for _ in 0..size {
+                        cur.read_exact(&mut buf)?;
                         res.push(LittleEndian::read_f64(&buf));
-                    });
+                    }
                     Ok(DataValue::REAL(res))
                 },
             }
@@ -218,7 +232,7 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
         pub fn get_data_field(&self, field_name: &str) -> Option<BlockField> {
             match &self.data {
                 Value::Table(table) => {
-                    Some(table.get(field_name)?.clone().try_into().unwrap())
+                    table.get(field_name)?.clone().try_into().ok()
                 },
                 _ => {
                     None
@@ -229,7 +243,7 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
         pub fn get_link_block_type(&self, link_name: &str) -> Option<Vec<String>> {
             match &self.link {
                 Value::Table(table) => {
-                    Some(table.get(link_name)?.clone().try_into::<Vec<String>>().unwrap())
+                    table.get(link_name)?.clone().try_into::<Vec<String>>().ok()
                 },
                 _ => {
                     None
@@ -237,14 +251,18 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
             }
         }
 
-        pub fn try_parse_buf(&self, buf: &mut Cursor<&[u8]>, offset: u64) -> Result<BlockInfo, Box<dyn std::error::Error>>{
+        pub fn try_parse_buf(&self, buf: &mut Cursor<&[u8]>, offset: u64) -> Result<BlockInfo, Mf4Error>{
+            self.try_parse_buf_inner(buf, offset).map_err(|e| e.with_offset(offset))
+        }
+
+        fn try_parse_buf_inner(&self, buf: &mut Cursor<&[u8]>, offset: u64) -> Result<BlockInfo, Mf4Error>{
             // read id
-            buf.seek(SeekFrom::Start(offset)).unwrap();
+            buf.seek(SeekFrom::Start(offset))?;
             let mut id_buf = [0u8;4];
-            buf.read_exact(&mut id_buf).unwrap();
+            buf.read_exact(&mut id_buf)?;
             if !self.check_id(&id_buf) {
-                //println!("Invalid block id");     // TODO: debug info  put into logger
-                return Err("Invalid block id".into());
+                // link points to another block type or into the middle of a block
+                return Err(Mf4Error::corrupt_link(offset));
             } else {
                 let mut blk_info: BlockInfo = BlockInfo {
                     links: Vec::new(),
@@ -254,41 +272,94 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                 };
                 // read 20 more bytes
                 let mut data_buf: [u8; 20] = [0u8;20];
-                buf.read_exact(&mut data_buf).unwrap();
+                buf.read_exact(&mut data_buf)?;
                 // parse length and link count
                 let blk_len = LittleEndian::read_u64(&data_buf[4..12]);
                 let link_count: u64 = LittleEndian::read_u64(&data_buf[12..20]);
+                let file_left: u64 = (buf.get_ref().len() as u64).saturating_sub(offset);
+                if blk_len < 24 || blk_len > file_left || link_count > (blk_len - 24) / 8 {
+                    return Err(format!("Invalid block length {} with {} links", blk_len, link_count).into());
+                }
                 // decide to read how many bytes using blk_len
                 let mut vec_buf: Vec<u8> = vec![0u8;blk_len as usize -24];
-                buf.read_exact(&mut vec_buf).unwrap();
+                buf.read_exact(&mut vec_buf)?;
                 let mut cur = Cursor::new(&vec_buf);
                 if link_count > 0 {
                     let mut link_buf: [u8; 8] = [0u8;8];   // link are 8 bytes long 
                     // it is very important that link fields are ordered just like in toml file
                     for _ in 0..link_count {
-                        cur.read_exact(&mut link_buf).unwrap();
+                        cur.read_exact(&mut link_buf)?;
                         let link_offset = LittleEndian::read_u64(&link_buf);
                         blk_info.links.push(link_offset);
                     }
                 }
                 // read parse data using datafield's try_parse_value method
-                for dname in self.get_data_fields().unwrap() {
-                    let field = self.get_data_field(dname).unwrap();  // panic if no field description found
+                for dname in self.get_data_fields().unwrap_or_default() {
+                    let field = self.get_data_field(dname).ok_or("no field description found")?;
                     let data_value: DataValue = field.try_parse_value(&mut cur)?;
                     blk_info.data.insert(dname.clone(), data_value);
                 }
                 let mut left_bytes: Vec<u8> = Vec::new();
-                cur.read_to_end(&mut left_bytes).unwrap();
+                cur.read_to_end(&mut left_bytes)?;
                 if left_bytes.len() > 0 {
                     blk_info.data.insert("unparsed_data".to_string(), DataValue::BYTE(left_bytes));
                 }
-                blk_info.map_links().unwrap_or_else(|e| {
-                    println!("Failed to map links due to {}" , e);
-                });
+                blk_info.map_links()?;   // inconsistent link count is an error of this block
                 Ok(blk_info)
             }
         }
 
+        pub fn try_parse_buf_v3(&self, buf: &mut Cursor<&[u8]>, offset: u64) -> Result<BlockInfo, Mf4Error> {
+            // MDF 3.x block: 2 bytes id + 2 bytes block size; links are 4 bytes long and have fixed count
+            if offset == 0 {
+                return Err(Mf4Error::corrupt_link(0));
+            }
+            buf.seek(SeekFrom::Start(offset))?;
+            let mut id_buf = [0u8;2];
+            buf.read_exact(&mut id_buf)?;
+            if !self.check_id(&id_buf) {
+                return Err(Mf4Error::corrupt_link(offset));
+            }
+            let mut two_bytes = [0u8;2];
+            buf.read_exact(&mut two_bytes)?;
+            let blk_len = LittleEndian::read_u16(&two_bytes) as usize;
+            if blk_len < 4 {
+                return Err(format!("Invalid block size at offset: {}", offset).into());
+            }
+            let mut vec_buf: Vec<u8> = vec![0u8; blk_len - 4];
+            buf.read_exact(&mut vec_buf)?;
+            let mut cur = Cursor::new(&vec_buf);
+            let mut blk_info: BlockInfo = BlockInfo {
+                links: Vec::new(),
+                data: IndexMap::new(),
+                id: self.id.to_owned(),
+                link_map: IndexMap::new()
+            };
+            let mut four_bytes = [0u8;4];
+            for name in self.get_link_fields().unwrap_or_default() {
+                cur.read_exact(&mut four_bytes)?;
+                let link_offset = LittleEndian::read_u32(&four_bytes) as u64;
+                blk_info.links.push(link_offset);
+                blk_info.link_map.insert(name.clone(), LinkAddr::Normal(link_offset));
+            }
+            // older versions have shorter blocks; missing trailing fields are simply not inserted
+            for dname in self.get_data_fields().unwrap_or_default() {
+                let field = self.get_data_field(dname).ok_or("no field description found")?;
+                let left = vec_buf.len() - cur.position() as usize;
+                if field.get_byte_len().is_some_and(|len| len > left) {
+                    break;
+                }
+                let data_value: DataValue = field.try_parse_value(&mut cur)?;
+                blk_info.data.insert(dname.clone(), data_value);
+            }
+            let mut left_bytes: Vec<u8> = Vec::new();
+            cur.read_to_end(&mut left_bytes)?;
+            if !left_bytes.is_empty() {
+                blk_info.data.insert("unparsed_data".to_string(), DataValue::BYTE(left_bytes));
+            }
+            Ok(blk_info)
+        }
+
     }
     
     #[derive(Debug)]
@@ -328,10 +399,10 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
     }
 
     impl BlockInfo {
-        pub fn map_links(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        pub fn map_links(&mut self) -> Result<(), Mf4Error> {
             match self.id.as_str() {
                 "##LD" => {  // these blocks have variable size links
-                    Err("Not Implemented".into())
+                    Err(Mf4Error::unsupported("link mapping of LD block"))
                 }
                 "##CN" => {  // these blocks have variable size links
                     let at_ref_nums:Vec<u16> = self.data.get(&"cn_attachment_count".to_string()).
@@ -340,11 +411,11 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                                                 ok_or("can not find cn_flags")?.clone().try_into()?;
                     let default_x_flag: bool = cn_flags[0] & (0x01<<12) == (0x01<<12);
                     //collect normal link first
-                    let cn_desc = get_block_desc_by_name("CN".to_string()).unwrap();
+                    let cn_desc = get_block_desc_by_name("CN".to_string()).ok_or("CN block not found")?;
                     let cn_link_fields = cn_desc.get_link_fields().ok_or("can not find cn_link_fields")?;
                     let mut i = 0;
                     for name in cn_link_fields {
-                        let link_addr = self.links[i];
+                        let link_addr = *self.links.get(i).ok_or("Invalid link count")?;
                         self.link_map.insert(name.clone(), LinkAddr::Normal(link_addr));
                         i += 1;
                     }
@@ -373,13 +444,94 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                     }
                     Ok(())
                 } // ##CN
+                "##EV" => {
+                    let scope_count: Vec<u32> = self.data.get("ev_scope_count").ok_or("can not find ev_scope_count")?.clone().try_into()?;
+                    let at_count: Vec<u16> = self.data.get("ev_attachment_count").ok_or("can not find ev_attachment_count")?.clone().try_into()?;
+                    let ev_desc = get_block_desc_by_name("EV".to_string()).ok_or("EV block not found")?;
+                    let ev_link_fields = ev_desc.get_link_fields().ok_or("can not find ev_link_fields")?;
+                    let mut i = 0;
+                    for name in ev_link_fields {
+                        let link_addr = *self.links.get(i).ok_or("Invalid link count")?;
+                        self.link_map.insert(name.clone(), LinkAddr::Normal(link_addr));
+                        i += 1;
+                    }
+                    for (name, count) in [("ev_scope", scope_count[0] as usize), ("ev_at_reference", at_count[0] as usize)] {
+                        if i + count > self.links.len() {
+                            return Err("Invalid link count".into());
+                        }
+                        self.link_map.insert(name.to_string(), LinkAddr::Variable(self.links[i..i+count].to_vec()));
+                        i += count;
+                    }
+                    if let Some(group_name) = self.links.get(i) {  // MDF 4.2
+                        self.link_map.insert("ev_tx_group_name".to_string(), LinkAddr::Normal(*group_name));
+                    }
+                    Ok(())
+                },
+                "##CH" => {
+                    let element_count: Vec<u32> = self.data.get("ch_element_count").ok_or("can not find ch_element_count")?.clone().try_into()?;
+                    let ch_desc = get_block_desc_by_name("CH".to_string()).ok_or("CH block not found")?;
+                    let ch_link_fields = ch_desc.get_link_fields().ok_or("can not find ch_link_fields")?;
+                    let mut i = 0;
+                    for name in ch_link_fields {
+                        let link_addr = *self.links.get(i).ok_or("Invalid link count")?;
+                        self.link_map.insert(name.clone(), LinkAddr::Normal(link_addr));
+                        i += 1;
+                    }
+                    let count = element_count[0] as usize * 3;   // DG, CG, CN triplets
+                    if i + count > self.links.len() {
+                        return Err("Invalid link count".into());
+                    }
+                    self.link_map.insert("ch_element".to_string(), LinkAddr::Variable(self.links[i..i+count].to_vec()));
+                    Ok(())
+                },
+                "##CA" => {
+                    let ndim: Vec<u16> = self.data.get("ca_ndim").ok_or("can not find ca_ndim")?.clone().try_into()?;
+                    let storage: Vec<u8> = self.data.get("ca_storage").ok_or("can not find ca_storage")?.clone().try_into()?;
+                    let flags: Vec<u32> = self.data.get("ca_flags").ok_or("can not find ca_flags")?.clone().try_into()?;
+                    let ndim: usize = ndim[0] as usize;
+                    let flags: u32 = flags[0];
+                    // number of elements; dim sizes are the first bytes of unparsed data
+                    let elements: usize = match self.data.get("unparsed_data") {
+                        Some(DataValue::BYTE(bytes)) if bytes.len() >= ndim * 8 => bytes[..ndim * 8].chunks_exact(8)
+                            .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as usize).product(),
+                        _ => return Err("Invalid ca data without dim size".into()),
+                    };
+                    let ca_desc = get_block_desc_by_name("CA".to_string()).ok_or("CA block not found")?;
+                    let ca_link_fields = ca_desc.get_link_fields().ok_or("can not find ca_link_fields")?;
+                    let mut i = 0;
+                    for name in ca_link_fields {
+                        let link_addr = *self.links.get(i).ok_or("Invalid link count")?;
+                        self.link_map.insert(name.clone(), LinkAddr::Normal(link_addr));
+                        i += 1;
+                    }
+                    let variable_links = [
+                        ("ca_data", if storage[0] == 2 { elements } else { 0 }),
+                        ("ca_dynamic_size", if flags & 0x01 != 0 { 3 * ndim } else { 0 }),
+                        ("ca_input_quantity", if flags & 0x02 != 0 { 3 * ndim } else { 0 }),
+                        ("ca_output_quantity", if flags & 0x04 != 0 { 3 } else { 0 }),
+                        ("ca_comparison_quantity", if flags & 0x08 != 0 { 3 } else { 0 }),
+                        ("ca_cc_axis_conversion", if flags & 0x10 != 0 { ndim } else { 0 }),
+                        ("ca_axis", if flags & 0x10 != 0 && flags & 0x20 == 0 { 3 * ndim } else { 0 }),
+                    ];
+                    for (name, count) in variable_links {
+                        if count == 0 {
+                            continue;
+                        }
+                        if i + count > self.links.len() {
+                            return Err("Invalid link count".into());
+                        }
+                        self.link_map.insert(name.to_string(), LinkAddr::Variable(self.links[i..i+count].to_vec()));
+                        i += count;
+                    }
+                    Ok(())
+                },
                 "##CC" => { 
-                    let cc_ref_count: Vec<u16>= self.data.get(&"cc_ref_count".to_string()).unwrap().clone().try_into()?;
-                    let cc_desc = get_block_desc_by_name("CC".to_string()).unwrap();
+                    let cc_ref_count: Vec<u16>= self.data.get(&"cc_ref_count".to_string()).ok_or("can not find cc_ref_count")?.clone().try_into()?;
+                    let cc_desc = get_block_desc_by_name("CC".to_string()).ok_or("CC block not found")?;
                     let cc_link_fields = cc_desc.get_link_fields().ok_or("can not find cc_link_fields")?;
                     let mut i = 0;
                     for name in cc_link_fields {
-                        let link_addr = self.links[i];
+                        let link_addr = *self.links.get(i).ok_or("Invalid link count")?;
                         self.link_map.insert(name.clone(), LinkAddr::Normal(link_addr));
                         i += 1;
                     }
@@ -399,8 +551,9 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
                 },// these blocks have variable size links
                 id => {// This is the normal case
                     let block_type:String = id[2..].to_string();
-                    let block_desc: &BlockDesc = get_block_desc_by_name(block_type).unwrap();
-                    for (i, name) in (0..self.links.len()).zip(block_desc.get_link_fields().unwrap()) {
+                    let block_desc: &BlockDesc = get_block_desc_by_name(block_type.clone())
+                                                     .ok_or(Mf4Error::unsupported(format!("{} block", block_type)))?;
+                    for (i, name) in (0..self.links.len()).zip(block_desc.get_link_fields().unwrap_or_default()) {
                         let link_addr = self.links[i];
                         self.link_map.insert(name.to_owned(), LinkAddr::Normal(link_addr));
                     }
@@ -409,10 +562,10 @@ pub mod block {  // utility struct and functions for parsing mdf block link and
             }
         }
         pub fn get_link_offset_normal(&self, link_name: &str) -> Option<u64> {
-            Some(self.link_map.get(&link_name.to_string())?.clone().try_into().unwrap())
+            self.link_map.get(&link_name.to_string())?.clone().try_into().ok()
         }
         pub fn get_link_offset_vec(&self, link_name: &str) -> Option<Vec<u64>> {
-            Some(self.link_map.get(&link_name.to_string())?.clone().try_into().unwrap())
+            self.link_map.get(&link_name.to_string())?.clone().try_into().ok()
         }
         pub fn get_data_value(&self, data_name: &str) -> Option<&DataValue> {
             Some(self.data.get(data_name)?)
@@ -446,15 +599,26 @@ pub mod parser {
     use std::sync::mpsc;
     use byteorder::{LittleEndian, ByteOrder};
     use std::collections::{HashMap, HashSet};
-    use chrono::DateTime;
+    use chrono::{DateTime, FixedOffset, NaiveDateTime};
     use lazy_static::lazy_static;
     use lru::LruCache;
     use std::num::NonZeroUsize;
     use std::cell::RefCell;
     use crate::components::dg::datagroup::{DataGroup, ChannelLink};
+    use crate::components::dx::dataxxx::{new_dz_cache, DzCache, DEFAULT_DZ_CACHE_SIZE};
+    use crate::components::at::attachment::Attachment;
+    use crate::components::fh::filehistory::FileHistory;
+    use crate::components::ev::event::{Event, RangeType};
+    use crate::components::ch::channelhierarchy::ChannelHierarchy;
+    use crate::components::sr::samplereduction::ReducedSeries;
+    use crate::components::md::metadata::{MdComment, CommentKind};
+    use crate::components::hd::header::Header;
+    use crate::components::cn::channel::SyncType;
+    use indexmap::IndexMap;
+    use crate::components::ca::channelarray::{ArrayAxis, ArrayValue, AxisSource, ChannelArray};
     use memmap2::Mmap;
 
-    type DynError = Box<dyn std::error::Error>;
+    use crate::error::Mf4Error;
     #[derive(RustEmbed)]
     #[folder = "config/"]
     #[prefix = "config/"]
@@ -467,57 +631,135 @@ pub mod parser {
         pub time_stamp: u64,
         pub date_time: String,
         pub first_dg_offset: u64,
+        pub first_at_offset: u64,
+        pub first_fh_offset: u64,
+        pub first_ev_offset: u64,
+        pub first_ch_offset: u64,
+        pub header: Header,
+        pub comment: MdComment,   // HDcomment; author, department, project and subject of MDF 3.x header are properties
+        pub big_endian: bool,   // default byte order of MDF 3.x files; always false for MDF 4.x
     }
 
     impl MdfInfo {
-        pub fn new(file: &mut Cursor<&[u8]>) -> Result<Self, DynError>{
+        pub fn new(file: &mut Cursor<&[u8]>) -> Result<Self, Mf4Error>{
             // manually parse id block
             file.seek(SeekFrom::Start(0))?;
             let mut buf = [0u8;8];
             let mut two_bytes: [u8;2] = [0u8;2];
             file.read_exact(&mut buf)?;
-            if std::str::from_utf8(&buf).unwrap() != "MDF     " {
-                return Err("not a mdf file".into());
+            if &buf != b"MDF     " {
+                return Err(Mf4Error::BadMagic { found: String::from_utf8_lossy(&buf).to_string() });
             }
             // read version
             file.read_exact(&mut buf)?;
             let version = String::from_utf8(buf.to_vec())?.trim().to_string();
 
-            file.seek(SeekFrom::Current(12))?; // skip 12 bytes
+            file.read_exact(&mut buf)?;
+            let program_id = String::from_utf8_lossy(&buf).trim_end_matches('\0').trim().to_string();
+            file.seek(SeekFrom::Current(4))?; // skip 4 bytes
             // read version number
             file.read_exact(&mut two_bytes)?;
             let version_num = LittleEndian::read_u16(&two_bytes);
-            if version_num < 400 {
-                panic!("unsupported version: {}", version_num);   // do not support any version below 4.00
+            if !(300..500).contains(&version_num) {
+                // do not support any version below 3.00 or any future major version
+                return Err(Mf4Error::UnsupportedVersion { version: format!("{} ({})", version, version_num) });
+            } else if version_num < 400 {
+                return Self::new_v3(file, version, version_num, program_id);
             }
             file.seek(SeekFrom::Current(30))?; // skip 30 bytes
             file.read_exact(&mut two_bytes)?; //id_unfin_flags
             file.read_exact(&mut two_bytes)?; //id_custom_unfin_flags
-            let offset = file.stream_position().unwrap();
+            let offset = file.stream_position()?;
             //parse header HD block
             let block: &BlockDesc = get_block_desc(file, 0x40)?;
             let header_info: BlockInfo = block.try_parse_buf(file, offset)?;
-            let first_dg_offset: u64 = header_info.get_link_offset_normal("hd_dg_first").unwrap();
+            let first_dg_offset: u64 = header_info.get_link_offset_normal("hd_dg_first").ok_or("hd_dg_first not found")?;
+            let first_at_offset: u64 = header_info.get_link_offset_normal("hd_at_first").unwrap_or(0);
+            let first_fh_offset: u64 = header_info.get_link_offset_normal("hd_fh_first").unwrap_or(0);
+            let first_ev_offset: u64 = header_info.get_link_offset_normal("hd_ev_first").unwrap_or(0);
+            let first_ch_offset: u64 = header_info.get_link_offset_normal("hd_ch_first").unwrap_or(0);
+            let comment: MdComment = MdComment::parse(&get_clean_text(file, header_info.get_link_offset_normal("hd_md_comment").unwrap_or(0))
+                                                          .unwrap_or("".to_string()));
             //parse time stamp
-            let time_stamp_v = header_info.get_data_value("hd_start_time_ns").unwrap();
-            let t: Vec<u64> = time_stamp_v.clone().try_into().unwrap();
-            let dt = DateTime::from_timestamp_nanos(t[0] as i64);
-            let time_stamp = t[0];
+            let time_stamp: u64 = header_info.get_data_value_first("hd_start_time_ns").ok_or("hd_start_time_ns not found")?;
+            let dt = DateTime::from_timestamp_nanos(time_stamp as i64);
             let date_time = dt.format("%Y-%m-%d %H:%M:%S%.9f").to_string();   
+            let header: Header = Header::new(&header_info, program_id)?;
+            Ok(Self{
+                version,
+                version_num,
+                time_stamp,
+                date_time,
+                first_dg_offset,
+                first_at_offset,
+                first_fh_offset,
+                first_ev_offset,
+                first_ch_offset,
+                header,
+                comment,
+                big_endian: false,
+            })
+        }
+
+        fn new_v3(file: &mut Cursor<&[u8]>, version: String, version_num: u16, program_id: String) -> Result<Self, Mf4Error> {
+            // MDF 3.x stores the default byte order in ID block; it applies to all channels without explicit byte order
+            let mut two_bytes: [u8;2] = [0u8;2];
+            file.seek(SeekFrom::Start(24))?;
+            file.read_exact(&mut two_bytes)?;
+            let big_endian = LittleEndian::read_u16(&two_bytes) != 0;
+            let block: &BlockDesc = get_block_desc_v3_by_name("HD".to_string()).ok_or("HD block not found")?;
+            let header_info: BlockInfo = block.try_parse_buf_v3(file, 0x40)?;
+            let first_dg_offset: u64 = header_info.get_link_offset_normal("hd_dg_first").ok_or("hd_dg_first not found")?;
+            let time_stamp: u64 = if let Some(local_ns) = header_info.get_data_value_first::<u64>("hd_start_time_ns") {
+                // since 3.20 time stamp is local time; utc offset is given in hours
+                let utc_offset_hour = header_info.get_data_value_first::<i16>("hd_utc_offset_hour").unwrap_or(0);
+                (local_ns as i64 - utc_offset_hour as i64 * 3_600_000_000_000) as u64
+            } else {
+                let date: String = header_info.get_data_value_copy("hd_date").ok_or("hd_date not found")?.try_into()?;
+                let time: String = header_info.get_data_value_copy("hd_time").ok_or("hd_time not found")?.try_into()?;
+                let dt = NaiveDateTime::parse_from_str(format!("{} {}", date.trim_end_matches('\0'), time.trim_end_matches('\0')).as_str(),
+                                                       "%d:%m:%Y %H:%M:%S")?;
+                dt.and_utc().timestamp_nanos_opt().ok_or("time stamp out of range")? as u64
+            };
+            let date_time = DateTime::from_timestamp_nanos(time_stamp as i64)
+                                .format("%Y-%m-%d %H:%M:%S%.9f").to_string();
+            let text: String = get_clean_text_v3(file, header_info.get_link_offset_normal("hd_tx_comment").unwrap_or(0))
+                                .unwrap_or("".to_string());
+            let mut properties: IndexMap<String, String> = IndexMap::new();
+            for (name, field) in [("author", "hd_author"), ("department", "hd_organization"), ("project", "hd_project"), ("subject", "hd_subject")] {
+                let value: String = header_info.get_data_value_copy(field).and_then(|v| v.try_into().ok()).unwrap_or_default();
+                properties.insert(name.to_string(), value.trim_end_matches('\0').trim().to_string());
+            }
+            let comment = MdComment::from_parts(CommentKind::HD, text, properties);
+            let utc_offset_hour: Option<i16> = header_info.get_data_value_first::<u64>("hd_start_time_ns")
+                                    .and(header_info.get_data_value_first::<i16>("hd_utc_offset_hour"));
+            let time_quality: u16 = header_info.get_data_value_first("hd_time_quality").unwrap_or(0);
+            let header: Header = Header::new_v3(program_id, time_stamp, utc_offset_hour, time_quality);
             Ok(Self{
                 version,
                 version_num,
                 time_stamp,
                 date_time,
                 first_dg_offset,
+                first_at_offset: 0,   // no attachments in MDF 3.x
+                first_fh_offset: 0,
+                first_ev_offset: 0,
+                first_ch_offset: 0,
+                header,
+                comment,
+                big_endian,
             })
         }
+
+        pub fn is_v3(&self) -> bool {
+            self.version_num < 400
+        }
     }
 
     lazy_static! {
         pub static ref DESC_MAP: HashMap<String, BlockDesc> = {
             let mut m = HashMap::new();
-            let block_types = ["DG", "HD", "CG", "TX", "MD", "CN", "CC", "SI", "CA", "DZ", "HL"];
+            let block_types = ["DG", "HD", "CG", "TX", "MD", "CN", "CC", "SI", "CA", "DZ", "HL", "AT", "FH", "EV", "CH", "SR"];
             block_types.into_iter().for_each(|key| {
                 let desc = parse_toml(key.to_lowercase().as_str()).unwrap();  // toml file names in lowercase
                 m.insert(key.to_string(), desc);  // key in uppercase
@@ -525,24 +767,40 @@ pub mod parser {
             m
         };
     }
+    lazy_static! {
+        pub static ref DESC_MAP_V3: HashMap<String, BlockDesc> = {
+            let mut m = HashMap::new();
+            let block_types = ["HD", "TX", "DG", "CG", "CN", "CC", "CE"];
+            block_types.into_iter().for_each(|key| {
+                let desc = parse_toml(format!("v3/{}", key.to_lowercase()).as_str()).unwrap();
+                m.insert(key.to_string(), desc);
+            });
+            m
+        };
+    }
     pub fn get_block_desc_by_name(name: String) -> Option<&'static BlockDesc> {
         let name = name.to_uppercase();
         DESC_MAP.get(&name)
     }
 
-    pub fn get_block_desc<'a>(file: &'a mut Cursor<&[u8]>, offset: u64) -> Result<&'static BlockDesc, DynError>{
+    pub fn get_block_desc_v3_by_name(name: String) -> Option<&'static BlockDesc> {
+        let name = name.to_uppercase();
+        DESC_MAP_V3.get(&name)
+    }
+
+    pub fn get_block_desc<'a>(file: &'a mut Cursor<&[u8]>, offset: u64) -> Result<&'static BlockDesc, Mf4Error>{
         //use file offset to acquire the actual block type and its block desc
         if offset == 0 {
-            return Err("Invalid offset".into());
+            return Err(Mf4Error::corrupt_link(0));
         }
         let mut buf: [u8; 4] = [0u8;4];
         file.seek(SeekFrom::Start(offset))?;
         file.read_exact(&mut buf)?;
-        let block_type: String = String::from_utf8(buf[2..].to_vec())?;
-        Ok(DESC_MAP.get(&block_type).unwrap())
+        let block_type: String = String::from_utf8_lossy(&buf[2..]).to_string();
+        DESC_MAP.get(&block_type).ok_or(Mf4Error::UnknownBlock { id: String::from_utf8_lossy(&buf).to_string(), offset })
     }
 
-    pub fn parse_toml(block_name: &str) -> Result<BlockDesc, DynError> {
+    pub fn parse_toml(block_name: &str) -> Result<BlockDesc, Mf4Error> {
         let mut path: PathBuf = PathBuf::from("config/");
         path.push(block_name);
         path.set_extension("toml");
@@ -550,86 +808,122 @@ pub mod parser {
         Ok(toml::from_str(std::str::from_utf8(toml_file.data.as_ref())?)?)
     }
     
+    fn visit_list_block(file: &Cursor<&[u8]>, visited: &mut HashSet<u64>, next: u64, first_child_offset: u64, min_blk_len: u64)
+        -> Result<(), Mf4Error> {
+        // a linked list is corrupt if it comes back to a block already read (cycle)
+        // or if it holds more blocks than the file has room for
+        let max_blocks: u64 = file.get_ref().len() as u64 / min_blk_len;
+        if !visited.insert(next) || visited.len() as u64 > max_blocks {
+            return Err(Mf4Error::CorruptLink { target: next, offset: Some(first_child_offset) });
+        }
+        Ok(())
+    }
+
     pub fn get_child_info<'a>(file: &mut Cursor<&[u8]>, first_child_offset: u64, block_type: &'static str) 
-        -> Result<Vec<BlockInfo>, DynError> {
+        -> Result<Vec<BlockInfo>, Mf4Error> {
         let mut link_list: Vec<BlockInfo> = Vec::new();
         let blk_str: String = block_type.to_lowercase();
-        let block_desc: &BlockDesc = DESC_MAP.get(block_type).unwrap();
+        let block_desc: &BlockDesc = DESC_MAP.get(block_type).ok_or(Mf4Error::unsupported(format!("{} block", block_type)))?;
         let link_name = format!("{0}_{0}_next", blk_str);   // there is a pattern for CN CG DG link-list
         let mut cursor = first_child_offset;
-        let mut counter = 0;
+        let mut visited: HashSet<u64> = HashSet::from([cursor]);
         loop {
             let blk: BlockInfo = block_desc.try_parse_buf(file, cursor)?;
-            cursor = blk.get_link_offset_normal(link_name.as_str()).unwrap();
+            cursor = blk.get_link_offset_normal(link_name.as_str()).ok_or("next link not found")?;
             link_list.push(blk);
             if cursor == 0 {
                 break;
             }
-            counter += 1; 
-            if counter > 1000 {
-                panic!("too many blocks in list at offset: {}", first_child_offset);
-            }
+            visit_list_block(file, &mut visited, cursor, first_child_offset, 24)?;
         }
         Ok(link_list)
     }
 
     pub fn get_child_links<'a>(file: &mut Cursor<&[u8]>, first_child_offset: u64, block_type: &'static str) 
-        -> Result<Vec<u64>, DynError> {
+        -> Result<Vec<u64>, Mf4Error> {
         let mut link_list: Vec<u64> = Vec::new();
         let blk_str: String = block_type.to_lowercase();
-        let block_desc: &BlockDesc = DESC_MAP.get(block_type).unwrap();
+        let block_desc: &BlockDesc = DESC_MAP.get(block_type).ok_or(Mf4Error::unsupported(format!("{} block", block_type)))?;
         let link_name = format!("{0}_{0}_next", blk_str);   // there is a pattern for CN CG DG link-list
         let mut cursor = first_child_offset;
         link_list.push(cursor);
-        let mut counter = 0;
+        let mut visited: HashSet<u64> = HashSet::from([cursor]);
         loop {
             let blk: BlockInfo = block_desc.try_parse_buf(file, cursor)?;
-            cursor = blk.get_link_offset_normal(link_name.as_str()).unwrap();
+            cursor = blk.get_link_offset_normal(link_name.as_str()).ok_or("next link not found")?;
             if cursor == 0 {
                 break;
             }
-            link_list.push(cursor);    
-            counter += 1; 
-            if counter > 1000 {
-                panic!("too many blocks in list at offset: {}", first_child_offset);
+            visit_list_block(file, &mut visited, cursor, first_child_offset, 24)?;
+            link_list.push(cursor);
+        }
+        Ok(link_list)
+    }
+
+    pub fn get_child_links_v3(file: &mut Cursor<&[u8]>, first_child_offset: u64, block_type: &'static str)
+        -> Result<Vec<u64>, Mf4Error> {
+        let mut link_list: Vec<u64> = Vec::new();
+        if first_child_offset == 0 {
+            return Ok(link_list)
+        }
+        let blk_str: String = block_type.to_lowercase();
+        let block_desc: &BlockDesc = DESC_MAP_V3.get(block_type).ok_or("unknown v3 block type")?;
+        let link_name = format!("{0}_{0}_next", blk_str);
+        let mut cursor = first_child_offset;
+        link_list.push(cursor);
+        let mut visited: HashSet<u64> = HashSet::from([cursor]);
+        loop {
+            let blk: BlockInfo = block_desc.try_parse_buf_v3(file, cursor)?;
+            cursor = blk.get_link_offset_normal(link_name.as_str()).ok_or("next link not found")?;
+            if cursor == 0 {
+                break;
             }
+            visit_list_block(file, &mut visited, cursor, first_child_offset, 4)?;
+            link_list.push(cursor);
         }
         Ok(link_list)
     }
 
-    pub fn get_tx_data(file: &mut Cursor<&[u8]>, tx_offset: u64) -> Result<String, DynError> {
+    pub fn get_tx_data(file: &mut Cursor<&[u8]>, tx_offset: u64) -> Result<String, Mf4Error> {
         let desc = get_block_desc(file, tx_offset)?;
         let tx_info: BlockInfo = desc.try_parse_buf(file, tx_offset)?;
-        Ok(tx_info.get_data_value("tx_data").unwrap().clone().try_into()?)
+        Ok(tx_info.get_data_value("tx_data").ok_or("tx_data not found")?.clone().try_into()?)
     }
 
-    pub fn get_text(file :&mut Cursor<&[u8]>, offset: u64) -> Result<String, DynError> {
+    pub fn get_text(file :&mut Cursor<&[u8]>, offset: u64) -> Result<String, Mf4Error> {
         let desc = get_block_desc(file, offset)?;
         let tx_info: BlockInfo = desc.try_parse_buf(file, offset)?;
         match tx_info.get_id().as_str() {
             "##TX" => {
-                let data = tx_info.get_data_value("tx_data").unwrap();
+                let data = tx_info.get_data_value("tx_data").ok_or("tx_data not found")?;
                 Ok(data.clone().try_into()?)
             },
             "##MD" => {
-                let data = tx_info.get_data_value("md_data").unwrap();
+                let data = tx_info.get_data_value("md_data").ok_or("md_data not found")?;
                 Ok(data.clone().try_into()?)
             },
             _ => {
-                Err(format!("unknown TEXT block at offset: {}", offset).into())
+                Err(Mf4Error::corrupt_link(offset))   // not a TX or MD block
             }
         }
     }
 
-    pub fn get_clean_text(file :&mut Cursor<&[u8]>, offset: u64) -> Result<String, DynError> {
+    pub fn get_clean_text(file :&mut Cursor<&[u8]>, offset: u64) -> Result<String, Mf4Error> {
         // TX and MD block will have \0 terminated string; use this function to remove the tailing \0
         let text = get_text(file, offset)?;
         Ok(text.trim_end_matches('\0').to_string())
     }
 
-    pub fn peek_block_type(file: &mut Cursor<&[u8]>, offset: u64) -> Result<String, DynError> {
+    pub fn get_clean_text_v3(file :&mut Cursor<&[u8]>, offset: u64) -> Result<String, Mf4Error> {
+        let desc: &BlockDesc = get_block_desc_v3_by_name("TX".to_string()).ok_or("TX block not found")?;
+        let tx_info: BlockInfo = desc.try_parse_buf_v3(file, offset)?;
+        let text: String = tx_info.get_data_value_copy("tx_data").ok_or("tx_data not found")?.try_into()?;
+        Ok(text.trim_end_matches('\0').to_string())
+    }
+
+    pub fn peek_block_type(file: &mut Cursor<&[u8]>, offset: u64) -> Result<String, Mf4Error> {
         if offset == 0 || offset % 8 != 0 {
-            Err("Invalid block start offset".into())
+            Err(Mf4Error::corrupt_link(offset))
         } else {
             let mut buf: [u8; 4] = [0u8;4];
             let orig_position = file.stream_position()?;
@@ -643,45 +937,103 @@ pub mod parser {
     
     pub struct Mdf where {
         pub mdfinfo: MdfInfo,
-        pub data: Vec<DataGroup>
+        pub data: Vec<DataGroup>,
+        pub attachments: Vec<Attachment>,
+        pub file_history: Vec<FileHistory>,
+        pub events: Vec<Event>,
+        pub hierarchy: Vec<ChannelHierarchy>,
+        pub warnings: Vec<Mf4Error>,  // blocks which could not be read and were skipped
+    }
+
+    fn read_block_list<B>(file: &mut Cursor<&[u8]>, first_offset: u64, block_type: &'static str, warnings: &mut Vec<Mf4Error>,
+                          read_block: fn(&mut Cursor<&[u8]>, u64) -> Result<B, Mf4Error>) -> Result<Vec<B>, Mf4Error> {
+        /* all blocks of a linked list; a block that fails to parse is skipped and its error kept as warning */
+        let mut blocks: Vec<B> = Vec::new();
+        if first_offset == 0 {
+            return Ok(blocks)
+        }
+        for link in get_child_links(file, first_offset, block_type)? {
+            match read_block(file, link) {
+                Ok(block) => blocks.push(block),
+                Err(e) => warnings.push(e.with_offset(link)),
+            }
+        }
+        Ok(blocks)
     }
 
     impl Mdf {
-        pub fn new<T>(file: &mut Cursor<&[u8]>, app: Option<&T>) -> Result<Self, DynError>  where T: Fn(f64) + 'static {
+        pub fn new<T>(file: &mut Cursor<&[u8]>, app: Option<&T>) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
+            Self::new_with_dz_cache(file, app, new_dz_cache(DEFAULT_DZ_CACHE_SIZE))
+        }
+
+        pub fn new_with_dz_cache<T>(file: &mut Cursor<&[u8]>, app: Option<&T>, dz_cache: DzCache) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
             let mdfinfo = MdfInfo::new(file)?;
             let mut data = Vec::new();
-            let dg_links = get_child_links(file, mdfinfo.first_dg_offset, "DG")?;
-            
+            let is_v3: bool = mdfinfo.is_v3();
+            let big_endian: bool = mdfinfo.big_endian;
+            let mut warnings: Vec<Mf4Error> = Vec::new();
+            let attachments: Vec<Attachment> = read_block_list(file, mdfinfo.first_at_offset, "AT", &mut warnings, Attachment::new)?;
+            let file_history: Vec<FileHistory> = read_block_list(file, mdfinfo.first_fh_offset, "FH", &mut warnings, FileHistory::new)?;
+            let events: Vec<Event> = read_block_list(file, mdfinfo.first_ev_offset, "EV", &mut warnings, Event::new)?;
+            let hierarchy: Vec<ChannelHierarchy> = read_block_list(file, mdfinfo.first_ch_offset, "CH", &mut warnings, ChannelHierarchy::new)?;
+            let dg_links = if mdfinfo.first_dg_offset == 0 {
+                Vec::new()   // file without any measurement data
+            } else if is_v3 {
+                get_child_links_v3(file, mdfinfo.first_dg_offset, "DG")?
+            } else {
+                get_child_links(file, mdfinfo.first_dg_offset, "DG")?
+            };
+
+            let dg_file: &mut Cursor<&[u8]> = &mut *file;
             thread::scope(|s| {
-                let (tx, rx) = mpsc::channel::<DataGroup>();
+                let (tx, rx) = mpsc::channel::<Result<DataGroup, Mf4Error>>();
                 let mut dg_count: usize = 0;
                 let total_len = dg_links.len();
                 s.spawn(move || {
-                    dg_links.iter().for_each(|dg_offset| {
-                        match DataGroup::new_unchecked(file, *dg_offset) {
-                            Ok(dg) => tx.send(dg).unwrap(),
-                            Err(e) => println!("Error: {} ;failed to create DataGroup at offset: {}", e, dg_offset),
+                    for dg_offset in dg_links.iter() {
+                        let dg = if is_v3 {
+                            DataGroup::new_v3(dg_file, *dg_offset, big_endian)
+                        } else {
+                            DataGroup::new_unchecked_with_cache(dg_file, *dg_offset, dz_cache.clone())
+                        };
+                        if tx.send(dg.map_err(|e| e.with_offset(*dg_offset))).is_err() {
+                            break   // receiver is gone
                         }
-                    });
+                    }
                 });
                 while let Ok(dg) = rx.recv() {
-                    data.push(dg);
+                    match dg {
+                        Ok(dg) => data.push(dg),
+                        Err(e) => warnings.push(e),
+                    }
                     dg_count += 1;
                     let progress = dg_count as f64/total_len as f64 * 100.0;
-                    if app.is_some() {
-                        app.unwrap()(progress);
+                    if let Some(app) = app {
+                        app(progress);
                     } else {
                         println!("total process: {}", progress);
                     }
                     
                 }
             });
+            // records of DG template array elements are read through data groups of their own
+            let element_groups: Vec<DataGroup> = data.iter_mut().flat_map(|dg| dg.take_element_groups()).collect();
+            data.extend(element_groups);
             Ok(Self{
                 mdfinfo,
                 data,
+                attachments,
+                file_history,
+                events,
+                hierarchy,
+                warnings,
             })
         }
 
+        pub fn get_warnings(&self) -> &Vec<Mf4Error> {
+            &self.warnings
+        }
+
         pub fn generate_channel_map(&self) -> HashMap<String, ChannelLink> {
             let mut map: HashMap<String, ChannelLink<'_>> = HashMap::new();
             for dg in self.data.iter() {
@@ -723,24 +1075,99 @@ pub mod parser {
         pub fn nth_dg(&self, index: usize) -> Option<&DataGroup> {
             self.data.get(index)
         }
+
+        pub fn get_attachments(&self) -> &Vec<Attachment> {
+            &self.attachments
+        }
+
+        pub fn get_file_history(&self) -> &Vec<FileHistory> {
+            &self.file_history
+        }
+
+        pub fn get_events(&self) -> &Vec<Event> {
+            &self.events
+        }
+
+        pub fn get_channel_hierarchy(&self) -> &Vec<ChannelHierarchy> {
+            &self.hierarchy
+        }
+
+        pub fn get_header_comment(&self) -> &MdComment {
+            &self.mdfinfo.comment
+        }
+
+        pub fn get_header(&self) -> &Header {
+            &self.mdfinfo.header
+        }
+
+        pub fn find_event(&self, offset: u64) -> Option<&Event> {
+            self.events.iter().find(|ev| ev.get_offset() == offset)
+        }
+
+        pub fn get_event_ranges(&self) -> Vec<(&Event, Option<&Event>)> {
+            // range begin events paired with the range end event pointing back to them
+            self.events.iter()
+                .filter(|ev| *ev.get_range_type() == RangeType::Begin)
+                .map(|begin| (begin, self.events.iter().find(|end| *end.get_range_type() == RangeType::End
+                                                               && end.get_range() == Some(begin.get_offset()))))
+                .collect()
+        }
+
+        pub fn find_channel_group(&self, offset: u64) -> Option<(usize, usize)> {
+            // (dg_index, cg_index) of the channel group at the block offset
+            self.data.iter().enumerate().find_map(|(dg_index, dg)| {
+                dg.get_channle_groups().iter().position(|cg| cg.get_offset() == offset)
+                  .map(|cg_index| (dg_index, cg_index))
+            })
+        }
     }
 
     
+    enum FileData {
+        Mapped(Mmap),
+        Owned(Vec<u8>),  // file content already in memory
+    }
+
+    impl std::ops::Deref for FileData {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            match self {
+                FileData::Mapped(mmap) => mmap,
+                FileData::Owned(data) => data,
+            }
+        }
+    }
+
     pub struct Mf4Wrapper{
         mdf: Mdf,
-        buf: Mmap,
+        buf: FileData,
+        path: PathBuf,  // external attachments are relative to the mf4 file
         channel_cache: HashMap<String, (usize, usize, usize)>, // (dg_index, cg_index, cn_index)
         master_cache: RefCell<LruCache<(usize, usize), DataValue>>  // (dg_index, cg_index)
     }
         
 
     impl Mf4Wrapper {
-        pub fn new<T>(file: PathBuf, app: Option<&T>) -> Result<Self, DynError>  where T: Fn(f64) + 'static {
-            let file_obj = File::open(file)?;
+        pub fn new<T>(file: PathBuf, app: Option<&T>) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
+            Self::new_with_dz_cache(file, app, DEFAULT_DZ_CACHE_SIZE)
+        }
+
+        pub fn new_with_dz_cache<T>(file: PathBuf, app: Option<&T>, dz_cache_size: usize) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
+            // dz_cache_size: max number of decompressed DZ blocks kept in memory
+            let file_obj = File::open(&file)?;
             let mmap = unsafe { Mmap::map(&file_obj)? };
-            let data: &[u8] = &mmap;
-            let mut buf: Cursor<&[u8]> = Cursor::new(data);
-            let mdf: Mdf = Mdf::new(&mut buf, app)?;
+            Self::from_file_data(FileData::Mapped(mmap), file, app, dz_cache_size)
+        }
+
+        pub fn from_bytes<T>(data: Vec<u8>, app: Option<&T>) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
+            /* file content already in memory; external attachments are resolved against the working directory */
+            Self::from_file_data(FileData::Owned(data), PathBuf::new(), app, DEFAULT_DZ_CACHE_SIZE)
+        }
+
+        fn from_file_data<T>(file_data: FileData, file: PathBuf, app: Option<&T>, dz_cache_size: usize) -> Result<Self, Mf4Error>  where T: Fn(f64) + 'static {
+            let mut buf: Cursor<&[u8]> = Cursor::new(&file_data);
+            let mdf: Mdf = Mdf::new_with_dz_cache(&mut buf, app, new_dz_cache(dz_cache_size))?;
             let mut channel_cache: HashMap<String, (usize, usize, usize)> = HashMap::new();
             for (dg_index, dg) in mdf.data.iter().enumerate() {
                 for (cg_index, cg) in dg.get_channle_groups().iter().enumerate() {
@@ -752,7 +1179,8 @@ pub mod parser {
             let master_cache = RefCell::new(LruCache::new(NonZeroUsize::new(5).unwrap()));
             Ok(Self {
                 mdf,
-                buf: mmap,
+                buf: file_data,
+                path: file,
                 channel_cache,
                 master_cache,
             })
@@ -785,24 +1213,165 @@ pub mod parser {
             } else { None }
         }
 
+        pub fn get_channel_validity(&self, channel_name: &str) -> Option<Vec<bool>> {
+            // true means the sample is valid
+            if let Some(ChannelLink(cn, cg, dg)) = self.get_channel_link(channel_name) {
+                let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
+                cn.get_validity(&mut buf, dg, cg).ok()
+            } else { None }
+        }
+
+        pub fn get_channel_data_with_validity(&self, channel_name: &str) -> Option<(DataValue, Vec<bool>)> {
+            let data = self.get_channel_data(channel_name)?;
+            let validity = self.get_channel_validity(channel_name)?;
+            Some((data, validity))
+        }
+
+        pub fn get_channel_reduced_data(&self, channel_name: &str) -> Option<Vec<ReducedSeries>> {
+            // min/mean/max series of each sample reduction of the channel group; empty if there is none
+            let ChannelLink(cn, cg, dg) = self.get_channel_link(channel_name)?;
+            let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
+            cg.get_sample_reductions().iter()
+              .map(|sr| sr.get_reduced_values(&mut buf, cg, cn, dg.get_dz_cache()))
+              .collect::<Result<Vec<ReducedSeries>, _>>().ok()
+        }
+
+        fn get_array_values(&self, array_name: &str) -> Option<(ChannelArray, Vec<Vec<f64>>)> {
+            // array block of CN template array and its values of each record
+            let prefix = format!("{}[", array_name);
+            let element: &String = self.channel_cache.keys().find(|k| k.starts_with(&prefix))?;
+            let ca: ChannelArray = self.get_channel_link(element)?.get_channel().get_array()?.clone();
+            let mut columns: Vec<Vec<f64>> = Vec::new();
+            for name in ca.generate_array_names(array_name) {
+                columns.push(self.get_channel_data(&name)?.try_into().ok()?);
+            }
+            let records: usize = columns.first().map(|c| c.len()).unwrap_or(0);
+            let values: Vec<Vec<f64>> = (0..records).map(|i| columns.iter().map(|c| c[i]).collect()).collect();
+            Some((ca, values))
+        }
+
+        pub fn get_channel_array(&self, array_name: &str) -> Option<ArrayValue> {
+            /* values of an array channel (e.g. a calibration curve or map) with its axes; 
+               array_name is the channel name without element index */
+            let (ca, values) = self.get_array_values(array_name)?;
+            let shape: Vec<usize> = ca.get_dim_sizes();
+            let mut axes: Vec<ArrayAxis> = Vec::with_capacity(shape.len());
+            for (dim, size) in shape.iter().enumerate() {
+                let conversion = ca.get_axis_conversion(dim).cloned();
+                let axis: ArrayAxis = match ca.get_axis_source(dim) {
+                    AxisSource::Fixed(raw) => {
+                        let values: Vec<f64> = match conversion.as_ref().filter(|cc| cc.get_cc_type().is_num()) {
+                            Some(cc) => raw.into_iter().map(|v| cc.convert_num_value(v)).collect(),
+                            None => raw,
+                        };
+                        ArrayAxis { name: String::new(), values, conversion }
+                    },
+                    AxisSource::Channel(_, _, cn) => {
+                        // axis channel is an array itself; values of its first record are used
+                        let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
+                        let name: String = crate::components::cn::channel::Channel::new(&mut buf, cn).ok()?.get_name().to_string();
+                        let values: Vec<f64> = self.get_array_values(&name)
+                                                   .and_then(|(_, v)| v.into_iter().next())
+                                                   .unwrap_or_else(|| (0..*size).map(|i| i as f64).collect());
+                        ArrayAxis { name, values, conversion }
+                    },
+                    AxisSource::Index => ArrayAxis { 
+                        name: String::new(), 
+                        values: (0..*size).map(|i| i as f64).collect(), 
+                        conversion 
+                    },
+                };
+                axes.push(axis);
+            }
+            Some(ArrayValue {
+                name: array_name.to_string(),
+                array_type: ca.get_type(),
+                shape,
+                axes,
+                values,
+            })
+        }
+
+        fn get_master_group_index(&self, channel_name: &str) -> Option<(usize, usize)> {
+            // (dg_index, cg_index) of the channel group holding the master channel
+            let (dg_index, cg_index, _) = *self.channel_cache.get(channel_name)?;
+            if let Some(remote) = self.mdf.nth_dg(dg_index)?.nth_cg(cg_index)?.get_remote_master() {
+                self.mdf.find_channel_group(remote)  // master channel lives in another channel group
+            } else {
+                Some((dg_index, cg_index))
+            }
+        }
+
         pub fn get_channel_master_data(&self, channel_name: &str) -> Option<DataValue> {
-            let (dg_index, cg_index, cn_index) = self.channel_cache.get(channel_name)?;
+            let (dg_index, cg_index) = self.get_master_group_index(channel_name)?;
             let mut master_cache = self.master_cache.borrow_mut();
-            if master_cache.contains(&(*dg_index, *cg_index)) {
-                let d = master_cache.get(&(*dg_index, *cg_index)); // get the data from the master cache
+            if master_cache.contains(&(dg_index, cg_index)) {
+                let d = master_cache.get(&(dg_index, cg_index)); // get the data from the master cache
                 d.map(|d| d.clone())
             } else {
-                let dg: &DataGroup = self.mdf.nth_dg(*dg_index)?;
-                let cg: &ChannelGroup = dg.nth_cg(*cg_index)?;
-                let cn: &crate::components::cn::channel::Channel = cg.nth_cn(*cn_index)?;
-                let cl: ChannelLink<'_> = ChannelLink(cn, cg, dg);
+                let dg: &DataGroup = self.mdf.nth_dg(dg_index)?;
+                let cg: &ChannelGroup = dg.nth_cg(cg_index)?;
+                let master: &crate::components::cn::channel::Channel = cg.get_master()?;
                 let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
-                let data = cl.get_master_channel_data(&mut buf).ok()?;
-                master_cache.put((*dg_index, *cg_index), data.clone());
+                let data = master.get_data(&mut buf, dg, cg).ok()?;
+                master_cache.put((dg_index, cg_index), data.clone());
                 Some(data)
             }
         }
 
+        pub fn get_channel_abs_time_ns(&self, channel_name: &str) -> Option<Vec<u64>> {
+            /* absolute time (ns since epoch, UTC) of each sample from header start time and master channel;
+               None if the start time is local, the master channel is not a time channel or a sample lies outside the u64 range */
+            let (dg_index, cg_index) = self.get_master_group_index(channel_name)?;
+            let master = self.mdf.nth_dg(dg_index)?.nth_cg(cg_index)?.get_master()?;
+            if master.get_sync_type() != &SyncType::Time {
+                return None
+            }
+            let start_ns: i64 = self.get_header().get_start_time_utc_ns()?;
+            let seconds: Vec<f64> = self.get_channel_master_data(channel_name)?.try_into().ok()?;
+            seconds.into_iter().map(|s| {
+                let delta_ns: f64 = (s * 1e9).round();
+                if !delta_ns.is_finite() || delta_ns.abs() >= i64::MAX as f64 {
+                    return None
+                }
+                u64::try_from(start_ns.checked_add(delta_ns as i64)?).ok()
+            }).collect()
+        }
+
+        pub fn get_channel_abs_date_time(&self, channel_name: &str) -> Option<Vec<DateTime<FixedOffset>>> {
+            // absolute time of each sample in the time zone of the header
+            let offset: FixedOffset = *self.get_header().get_start_date_time()?.offset();
+            Some(self.get_channel_abs_time_ns(channel_name)?.into_iter()
+                     .map(|ns| DateTime::from_timestamp_nanos(ns as i64).with_timezone(&offset))
+                     .collect())
+        }
+
+        pub fn get_channel_time_relative_to(&self, channel_name: &str, reference: &Mf4Wrapper) -> Option<Vec<f64>> {
+            /* master values in seconds on the time base of another file (relative to its start time);
+               used to put synchronized recordings of several files on one time axis */
+            let reference_ns: i128 = reference.get_header().get_start_time_utc_ns()? as i128;
+            Some(self.get_channel_abs_time_ns(channel_name)?.into_iter()
+                     .map(|ns| (ns as i128 - reference_ns) as f64 / 1e9)
+                     .collect())
+        }
+
+        pub fn get_sync_channel_attachment(&self, channel_name: &str) -> Option<&Attachment> {
+            // stream attachment (e.g. camera video) referenced by a synchronization channel
+            let at_offset: u64 = self.get_channel_link(channel_name)?.get_channel().get_sync_attachment()?;
+            self.mdf.get_attachments().iter().find(|at| at.get_offset() == at_offset)
+        }
+
+        pub fn get_sync_channel_positions(&self, channel_name: &str) -> Option<Vec<(f64, f64)>> {
+            /* pairs of master value and position in the attached stream (e.g. video frame index or 
+               time in seconds depending on cn_sync_type); None if the channel is no synchronization channel */
+            if !self.get_channel_link(channel_name)?.get_channel().is_sync_channel() {
+                return None
+            }
+            let master: Vec<f64> = self.get_channel_master_data(channel_name)?.try_into().ok()?;
+            let positions: Vec<f64> = self.get_channel_data(channel_name)?.try_into().ok()?;
+            Some(master.into_iter().zip(positions).collect())
+        }
+
         pub fn get_all_channel_groups(&self) -> Vec<&ChannelGroup> {
             self.mdf.get_all_channel_groups()
         }
@@ -810,6 +1379,83 @@ pub mod parser {
         pub fn get_time_stamp(&self) -> String {
             self.mdf.get_time_stamp()
         }
+
+        pub fn get_attachments(&self) -> &Vec<Attachment> {
+            self.mdf.get_attachments()
+        }
+
+        pub fn get_file_history(&self) -> &Vec<FileHistory> {
+            self.mdf.get_file_history()
+        }
+
+        pub fn get_events(&self) -> &Vec<Event> {
+            self.mdf.get_events()
+        }
+
+        pub fn get_warnings(&self) -> &Vec<Mf4Error> {
+            // errors of blocks which were skipped while opening the file
+            self.mdf.get_warnings()
+        }
+
+        pub fn get_header(&self) -> &Header {
+            // start time with time zone, time source and program identifier
+            self.mdf.get_header()
+        }
+
+        pub fn get_header_comment(&self) -> &MdComment {
+            // HDcomment with author, department, project and subject
+            self.mdf.get_header_comment()
+        }
+
+        pub fn get_channel_hierarchy(&self) -> &Vec<ChannelHierarchy> {
+            // root nodes of the channel hierarchy tree; empty if the file has no CH blocks
+            self.mdf.get_channel_hierarchy()
+        }
+
+        pub fn get_event_time_ns(&self, event: &Event) -> Option<u64> {
+            // absolute time in ns since epoch; None if the event is not time synchronized
+            event.get_abs_time_ns(u64::try_from(self.get_header().get_start_time_utc_ns()?).ok()?)
+        }
+
+        pub fn get_event_parent(&self, event: &Event) -> Option<&Event> {
+            self.mdf.find_event(event.get_parent()?)
+        }
+
+        pub fn get_event_ranges(&self) -> Vec<(&Event, Option<&Event>)> {
+            self.mdf.get_event_ranges()
+        }
+
+        pub fn get_event_scope_names(&self, event: &Event) -> Vec<String> {
+            /* names of channel groups and channels the event applies to */
+            let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
+            event.get_scope().iter().filter_map(|link| {
+                match peek_block_type(&mut buf, *link).ok()?.as_str() {
+                    "CG" => {
+                        let (dg_index, cg_index) = self.mdf.find_channel_group(*link)?;
+                        Some(self.mdf.nth_dg(dg_index)?.nth_cg(cg_index)?.get_acq_name().to_string())
+                    },
+                    "CN" => Some(crate::components::cn::channel::Channel::new(&mut buf, *link).ok()?.get_name().to_string()),
+                    _ => None,
+                }
+            }).collect()
+        }
+
+        pub fn extract_attachment(&self, index: usize) -> Result<Vec<u8>, Mf4Error> {
+            /* embedded or external attachment payload; fails if MD5 checksum does not match */
+            let at: &Attachment = self.mdf.get_attachments().get(index)
+                .ok_or_else(|| Mf4Error::unsupported(format!("attachment index {} of {} attachments", index, self.mdf.get_attachments().len())))?;
+            let data: Vec<u8> = if at.is_embedded() {
+                let mut buf: Cursor<&[u8]> = Cursor::new(&self.buf);
+                at.get_data(&mut buf)?
+            } else {
+                at.read_external(&self.path)?
+            };
+            if at.check_md5(&data) {
+                Ok(data)
+            } else {
+                Err(Mf4Error::from(format!("MD5 checksum mismatch for attachment {}", at.get_file_name())).with_offset(at.get_offset()))
+            }
+        }
         
         pub fn is_sorted(&self) -> bool {
             self.mdf.data.iter().all(|dg| dg.is_sorted())
@@ -884,6 +1530,7 @@ pub mod parser_test {
     use std::io::Cursor;
     use std::path::PathBuf;
     use crate::parser::*;
+    use crate::ChannelLink;
     use crate::block::*;
     use rstest::*;
     use memmap2::Mmap;
@@ -1008,10 +1655,842 @@ pub mod parser_test {
         // $CalibrationLog
     }
 
+    fn build_mdf3_file() -> Vec<u8> {
+        // minimal MDF 3.30 file: one DG, one CG, time channel and a uint16 channel with linear conversion
+        fn put(file: &mut Vec<u8>, offset: usize, bytes: &[u8]) {
+            if file.len() < offset + bytes.len() {
+                file.resize(offset + bytes.len(), 0);
+            }
+            file[offset..offset+bytes.len()].copy_from_slice(bytes);
+        }
+        fn block(id: &str, size: u16) -> Vec<u8> {
+            let mut b = id.as_bytes().to_vec();
+            b.extend_from_slice(&size.to_le_bytes());
+            b.resize(size as usize, 0);
+            b
+        }
+        let mut file: Vec<u8> = Vec::new();
+        put(&mut file, 0, b"MDF     3.30    TEST    ");
+        put(&mut file, 28, &330u16.to_le_bytes());
+        let mut hd = block("HD", 208);
+        hd[4..8].copy_from_slice(&272u32.to_le_bytes());
+        hd[18..28].copy_from_slice(b"17:10:2026");
+        hd[28..36].copy_from_slice(b"12:30:00");
+        hd[36..42].copy_from_slice(b"tester");
+        hd[100..107].copy_from_slice(b"project");
+        hd[164..172].copy_from_slice(&1_792_240_200_000_000_000u64.to_le_bytes());  // local time
+        hd[172..174].copy_from_slice(&2i16.to_le_bytes());
+        put(&mut file, 64, &hd);
+        let mut dg = block("DG", 28);
+        dg[8..12].copy_from_slice(&300u32.to_le_bytes());
+        dg[16..20].copy_from_slice(&848u32.to_le_bytes());
+        dg[20..22].copy_from_slice(&1u16.to_le_bytes());
+        put(&mut file, 272, &dg);
+        let mut cg = block("CG", 30);
+        cg[8..12].copy_from_slice(&330u32.to_le_bytes());
+        cg[18..20].copy_from_slice(&2u16.to_le_bytes());
+        cg[20..22].copy_from_slice(&10u16.to_le_bytes());
+        cg[22..26].copy_from_slice(&3u32.to_le_bytes());
+        put(&mut file, 300, &cg);
+        let mut time = block("CN", 228);
+        time[4..8].copy_from_slice(&558u32.to_le_bytes());
+        time[24..26].copy_from_slice(&1u16.to_le_bytes());
+        time[26..30].copy_from_slice(b"time");
+        time[188..190].copy_from_slice(&64u16.to_le_bytes());
+        time[190..192].copy_from_slice(&3u16.to_le_bytes());
+        put(&mut file, 330, &time);
+        let mut speed = block("CN", 228);
+        speed[8..12].copy_from_slice(&786u32.to_le_bytes());
+        speed[26..31].copy_from_slice(b"speed");
+        speed[186..188].copy_from_slice(&64u16.to_le_bytes());
+        speed[188..190].copy_from_slice(&16u16.to_le_bytes());
+        put(&mut file, 558, &speed);
+        let mut cc = block("CC", 62);
+        cc[22..26].copy_from_slice(b"km/h");
+        cc[44..46].copy_from_slice(&2u16.to_le_bytes());
+        cc[46..54].copy_from_slice(&1.0f64.to_le_bytes());
+        cc[54..62].copy_from_slice(&0.5f64.to_le_bytes());
+        put(&mut file, 786, &cc);
+        let mut data: Vec<u8> = Vec::new();
+        for (t, v) in [(0.0f64, 10u16), (0.1, 20), (0.2, 30)] {
+            data.extend_from_slice(&t.to_le_bytes());
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        put(&mut file, 848, &data);
+        file
+    }
+
+    #[test]
+    fn test_mdf3_file() {
+        let wrapper = Mf4Wrapper::new::<fn(f64)>(PathBuf::from("test/02.MDF"), None).unwrap();
+        assert_eq!(wrapper.get_time_stamp(), "2024-03-14 16:07:55.190309904");
+        let names = wrapper.get_channel_names();
+        assert_eq!(names.len(), 3426);
+        assert_eq!(names[..3], ["ZONE_2D_CRC".to_string(), "ZONE_2D_MsgCntr".to_string(), "TqCmdFA".to_string()]);
+        let counter: Vec<f64> = wrapper.get_channel_data("ZONE_2D_MsgCntr").unwrap().try_into().unwrap();
+        assert_eq!(counter[..5], [125.0, 126.0, 127.0, 128.0, 129.0]);
+        let master: Vec<f64> = wrapper.get_channel_master_data("ZONE_2D_MsgCntr").unwrap().try_into().unwrap();
+        assert_eq!(master.len(), counter.len());
+        assert!((master[0] - 0.000183096).abs() < 1e-12 && (master[1] - 0.002178096).abs() < 1e-12);
+        let speed: Vec<f64> = wrapper.get_channel_data("PEUR_MotSpd_Rdnt1").unwrap().try_into().unwrap();
+        assert_eq!(speed[..3], [2338.0, 2337.0, 2335.0]);
+        let crc: Vec<f64> = wrapper.get_channel_data("ZONE_2D_CRC").unwrap().try_into().unwrap();
+        assert_eq!(crc[..3], [42239.0, 64042.0, 53145.0]);
+        match wrapper.get_channel_data("TqCtrlFA").unwrap() {   // value to text conversion
+            crate::DataValue::MIXED(v) => assert_eq!(v[0].clone().into_string().unwrap(), "standby"),
+            other => panic!("unexpected data {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mdf3_wrapper() {
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(build_mdf3_file(), None).unwrap();
+        assert_eq!(wrapper.get_channel_names(), vec!["speed".to_string()]);
+        assert_eq!(wrapper.get_time_stamp(), "2026-10-17 10:30:00.000000000");
+        let data: Vec<f64> = wrapper.get_channel_data("speed").unwrap().try_into().unwrap();
+        assert_eq!(data, vec![6.0, 11.0, 16.0]);
+        let master: Vec<f64> = wrapper.get_channel_master_data("speed").unwrap().try_into().unwrap();
+        assert_eq!(master, vec![0.0, 0.1, 0.2]);
+        let ChannelLink(cn, _, _) = wrapper.get_channel_link("speed").unwrap();
+        assert_eq!(cn.get_unit(), "km/h");
+        assert_eq!(wrapper.get_header_comment().get_author(), Some("tester"));
+        assert_eq!(wrapper.get_header_comment().get_project(), Some("project"));
+        assert_eq!(wrapper.get_header_comment().get_subject(), Some(""));
+        assert_eq!(wrapper.get_header().get_program_id(), "TEST");
+        assert_eq!(wrapper.get_header().get_start_date_time().unwrap().to_rfc3339(), "2026-10-17T12:30:00+02:00");
+
+        // records beyond the end of the file, or a cycle count that cannot fit any file
+        let mut truncated: Vec<u8> = build_mdf3_file();
+        truncated.truncate(truncated.len() - 5);
+        let mut huge: Vec<u8> = build_mdf3_file();
+        huge[322..326].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut date: Vec<u8> = build_mdf3_file();
+        date[828..830].copy_from_slice(&132u16.to_le_bytes());   // date conversion is not supported
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(date, None).unwrap();
+        assert!(wrapper.get_channel_names().is_empty());
+        assert!(matches!(wrapper.get_warnings().first(), Some(crate::error::Mf4Error::Unsupported { offset: Some(786), .. })));
+        for file in [truncated, huge] {
+            let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(file, None).unwrap();
+            assert!(wrapper.get_channel_names().is_empty());
+            assert!(matches!(wrapper.get_warnings().first(), Some(crate::error::Mf4Error::Malformed { .. })));
+        }
+    }
+
+    #[test]
+    fn test_channel_validity() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let cn = builder.add_cn("speed", 0, 0, 0, 0, 16, 0b10, 3);
+        let cg = builder.add_cg(cn, 0, 3, 0, 2, 1);
+        let records: Vec<u8> = vec![1, 0, 0x00, 2, 0, 0x08, 3, 0, 0xF7];
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let (data, validity) = wrapper.get_channel_data_with_validity("speed").unwrap();
+        let data: Vec<f64> = data.try_into().unwrap();
+        assert_eq!(data, vec![1.0, 2.0, 3.0]);
+        assert_eq!(validity, vec![true, false, true]);
+    }
+
+    #[test]
+    fn test_malformed_files() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let cn = builder.add_cn("speed", 0, 0, 0, 0, 16, 0, 0);
+        let cg = builder.add_cg(cn, 0, 1, 0, 2, 0);
+        let dt = builder.add_block("DT", &[], &[1, 0]);
+        let dg = builder.add_dg(cg, dt, 0);
+        let good: Vec<u8> = builder.finish(dg);
+
+        let mut bad_magic = good.clone();
+        bad_magic[..8].copy_from_slice(b"XDF     ");
+        let mut buf = Cursor::new(bad_magic.as_slice());
+        assert!(matches!(Mdf::new::<fn(f64)>(&mut buf, None), Err(Mf4Error::BadMagic { .. })));
+
+        let mut bad_version = good.clone();
+        bad_version[28..30].copy_from_slice(&700u16.to_le_bytes());
+        let mut buf = Cursor::new(bad_version.as_slice());
+        assert!(matches!(Mdf::new::<fn(f64)>(&mut buf, None), Err(Mf4Error::UnsupportedVersion { .. })));
+
+        // data group link pointing into the middle of a block
+        let mut bad_link = good.clone();
+        let pos = 0x40 + 24;   // hd_dg_first
+        bad_link[pos..pos+8].copy_from_slice(&(dg + 4).to_le_bytes());
+        let mut buf = Cursor::new(bad_link.as_slice());
+        let err = Mdf::new::<fn(f64)>(&mut buf, None).err().unwrap();
+        assert!(matches!(err, Mf4Error::CorruptLink { target, .. } if target == dg + 4));
+
+        // block length beyond the end of file
+        let mut truncated = good.clone();
+        truncated.truncate(dg as usize + 30);
+        let mut buf = Cursor::new(truncated.as_slice());
+        let err = Mdf::new::<fn(f64)>(&mut buf, None).err().unwrap();
+        assert_eq!(err.get_offset(), Some(dg));
+        assert!(err.to_string().ends_with(&format!("(at offset {})", dg)));
+
+        // channel claims more attachment links than the block has
+        let mut bad_links = good.clone();
+        let pos = cn as usize + 24 + 8 * 8 + 22;   // cn_attachment_count
+        bad_links[pos..pos+2].copy_from_slice(&5u16.to_le_bytes());
+        let mut buf = Cursor::new(bad_links.as_slice());
+        let cn_desc = get_block_desc_by_name("CN".to_string()).unwrap();
+        assert!(matches!(cn_desc.try_parse_buf(&mut buf, cn), Err(Mf4Error::Malformed { offset: Some(off), .. }) if off == cn));
+
+        let mut buf = Cursor::new(good.as_slice());
+        assert!(Mdf::new::<fn(f64)>(&mut buf, None).is_ok());
+    }
+
+    #[test]
+    fn test_long_and_cyclic_lists() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let cn_links: Vec<u64> = (0..3000).map(|i| builder.add_cn(&format!("bit{}", i), 0, 0, (i / 8) as u32, (i % 8) as u8, 1, 0, 0))
+                                          .collect();
+        cn_links.windows(2).for_each(|w| builder.set_link(w[0], 0, w[1]));
+        let cg = builder.add_cg(cn_links[0], 0, 1, 0, 375, 0);
+        let dt = builder.add_block("DT", &[], &[0xAA; 375]);
+        let dg = builder.add_dg(cg, dt, 0);
+        let file: Vec<u8> = builder.finish(dg);
+        let mut buf = Cursor::new(file.as_slice());
+        assert_eq!(get_child_links(&mut buf, cn_links[0], "CN").unwrap(), cn_links);
+        assert_eq!(get_child_info(&mut buf, cn_links[0], "CN").unwrap().len(), 3000);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(file.clone(), None).unwrap();
+        assert_eq!(wrapper.get_channel_names().len(), 3000);
+        let data: Vec<f64> = wrapper.get_channel_data("bit2999").unwrap().try_into().unwrap();
+        assert_eq!(data, vec![1.0]);
+
+        // last channel links back to the second one
+        builder.set_link(cn_links[2999], 0, cn_links[1]);
+        let file: Vec<u8> = builder.finish(dg);
+        let mut buf = Cursor::new(file.as_slice());
+        let err = get_child_links(&mut buf, cn_links[0], "CN").err().unwrap();
+        assert!(matches!(err, Mf4Error::CorruptLink { target, offset: Some(first) } if target == cn_links[1] && first == cn_links[0]));
+        assert!(matches!(get_child_info(&mut buf, cn_links[0], "CN"), Err(Mf4Error::CorruptLink { .. })));
+        // a channel linking to itself
+        builder.set_link(cn_links[2999], 0, cn_links[2999]);
+        let file: Vec<u8> = builder.finish(dg);
+        let mut buf = Cursor::new(file.as_slice());
+        assert!(matches!(get_child_links(&mut buf, cn_links[2990], "CN"), Err(Mf4Error::CorruptLink { .. })));
+    }
+
+    #[test]
+    fn test_lazy_record_index() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let speed = builder.add_cn("speed", 0, 0, 0, 0, 16, 0, 0);
+        let gear = builder.add_cn("gear", 0, 0, 0, 0, 8, 0, 0);
+        let cg1 = builder.add_cg(speed, 1, 2, 0, 2, 0);
+        let cg2 = builder.add_cg(gear, 2, 3, 0, 1, 0);
+        builder.set_link(cg1, 0, cg2);
+        // unsorted records with 1 byte record id
+        let records: Vec<u8> = vec![2, 1, 1, 10, 0, 2, 2, 1, 20, 0, 2, 3];
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg1, dt, 1);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let ChannelLink(_, _, data_group) = wrapper.get_channel_link("speed").unwrap();
+        assert!(!data_group.is_sorted());
+        assert!(!data_group.is_indexed());   // opening the file does not scan records
+        let speed: Vec<f64> = wrapper.get_channel_data("speed").unwrap().try_into().unwrap();
+        assert_eq!(speed, vec![10.0, 20.0]);
+        assert!(data_group.is_indexed());
+        let gear: Vec<f64> = wrapper.get_channel_data("gear").unwrap().try_into().unwrap();
+        assert_eq!(gear, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_column_oriented() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        // master channel group stored in one DV block
+        let time = builder.add_cn("time", 2, 4, 0, 0, 64, 0, 0);
+        let master_cg = builder.add_cg(time, 0, 3, 0, 8, 0);
+        let time_data: Vec<u8> = [0.0f64, 0.5, 1.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let dv = builder.add_block("DV", &[], &time_data);
+        let master_dg = builder.add_dg(master_cg, dv, 0);
+        // value channel group with remote master; values and invalidation bits in two DV and DI blocks
+        let speed = builder.add_cn("speed", 0, 0, 0, 0, 16, 0b10, 1);
+        let broken = builder.add_cn("broken", 0, 0, 1, 0, 16, 0, 0);   // reaches beyond the 2 bytes of the column
+        builder.set_link(speed, 0, broken);
+        let value_cg = builder.add_cg(speed, 0, 3, 0, 2, 1);
+        builder.set_link(value_cg, 6, master_cg);
+        let dv1 = builder.add_block("DV", &[], &[1, 0, 2, 0]);
+        let dv2 = builder.add_block("DV", &[], &[3, 0]);
+        let di1 = builder.add_block("DI", &[], &[0b00, 0b10]);
+        let di2 = builder.add_block("DI", &[], &[0b00]);
+        let mut ld_data: Vec<u8> = Vec::new();
+        ld_data.extend_from_slice(&(0x01u32 | 0x01 << 31).to_le_bytes());
+        ld_data.extend_from_slice(&2u32.to_le_bytes());
+        ld_data.extend_from_slice(&2u64.to_le_bytes());
+        let ld = builder.add_block("LD", &[0, dv1, dv2, di1, di2], &ld_data);
+        let value_dg = builder.add_dg(value_cg, ld, 0);
+        builder.set_link(master_dg, 0, value_dg);
+        let file: Vec<u8> = builder.finish(master_dg);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(file.clone(), None).unwrap();
+        let ChannelLink(_, _, dg) = wrapper.get_channel_link("speed").unwrap();
+        assert!(dg.is_column_oriented());
+        let ChannelLink(cn, cg, dg) = wrapper.get_channel_link("broken").unwrap();
+        let err = cn.get_data(&mut Cursor::new(file.as_slice()), dg, cg).err().unwrap();
+        assert!(matches!(err, crate::error::Mf4Error::Malformed { offset: Some(o), .. } if o == value_cg));
+        let (data, validity) = wrapper.get_channel_data_with_validity("speed").unwrap();
+        let data: Vec<f64> = data.try_into().unwrap();
+        assert_eq!(data, vec![1.0, 2.0, 3.0]);
+        assert_eq!(validity, vec![true, false, true]);
+        let master: Vec<f64> = wrapper.get_channel_master_data("speed").unwrap().try_into().unwrap();
+        assert_eq!(master, vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_attachments() {
+        use flate2::{write::ZlibEncoder, Compression};
+        use std::io::Write;
+        let dbc: &[u8] = b"VERSION \"\"\nBO_ 100 Engine: 8 Vector__XXX\n";
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(dbc).unwrap();
+        let compressed: Vec<u8> = encoder.finish().unwrap();
+        let a2l: &[u8] = b"ASAP2_VERSION 1 71";
+        let dir = crate::test_utils::TempDir::new("attachments");
+        std::fs::write(dir.path().join("engine.a2l"), a2l).unwrap();
+
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let embedded = builder.add_at("engine.dbc", "application/x-dbc", 0b111, &compressed, dbc);
+        let external = builder.add_at("engine.a2l", "application/x-a2l", 0b100, &[], a2l);
+        let corrupted = builder.add_at("broken.dbc", "application/x-dbc", 0b101, b"changed", dbc);
+        builder.set_link(embedded, 0, external);
+        builder.set_link(external, 0, corrupted);
+        builder.set_link(0x40, 3, embedded);
+        let path = dir.path().join("attachments.mf4");
+        let cn = builder.add_cn("speed", 0, 0, 0, 0, 8, 0, 0);
+        let cg = builder.add_cg(cn, 0, 0, 0, 1, 0);
+        let dg = builder.add_dg(cg, 0, 0);
+        std::fs::write(&path, builder.finish(dg)).unwrap();
+
+        let wrapper = Mf4Wrapper::new::<fn(f64)>(path.clone(), None).unwrap();
+        let attachments = wrapper.get_attachments();
+        assert_eq!(attachments.len(), 3);
+        assert_eq!(attachments[0].get_file_name(), "engine.dbc");
+        assert_eq!(attachments[0].get_mime_type(), "application/x-dbc");
+        assert!(attachments[0].is_embedded() && attachments[0].is_compressed());
+        assert!(!attachments[1].is_embedded());
+        assert_eq!(attachments[1].resolve_path(&path), dir.path().join("engine.a2l"));
+        assert_eq!(wrapper.extract_attachment(0).unwrap(), dbc);
+        assert_eq!(wrapper.extract_attachment(1).unwrap(), a2l);
+        assert!(wrapper.extract_attachment(2).is_err());
+        drop(wrapper);
+
+        // at_embedded_size beyond the end of file
+        for embedded_size in [1024u64, u64::MAX] {
+            let mut builder = crate::test_utils::Mf4Builder::new();
+            let at = builder.add_at("engine.dbc", "application/x-dbc", 0b001, dbc, dbc);
+            let pos = at as usize + 24 + 4 * 8 + 32;
+            builder.buf[pos..pos+8].copy_from_slice(&embedded_size.to_le_bytes());
+            builder.set_link(0x40, 3, at);
+            let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(0), None).unwrap();
+            let err = wrapper.extract_attachment(0).err().unwrap();
+            assert!(matches!(err, crate::error::Mf4Error::Malformed { offset: Some(o), .. } if o == at));
+        }
+    }
+
+    #[test]
+    fn test_file_history() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let first = builder.add_fh(1_792_225_800_000_000_000, 60, 60, 0x02,
+            "<FHcomment><TX>created</TX><tool_id>Logger</tool_id><tool_vendor>ACME</tool_vendor><tool_version>2.1</tool_version><user_name>tester</user_name></FHcomment>");
+        let second = builder.add_fh(1_792_229_400_000_000_000, 0, 0, 0,
+            "<FHcomment>\n<TX>converted</TX>\n<tool_id>mf4_parse</tool_id>\n<tool_vendor/>\n<tool_version>0.1</tool_version>\n</FHcomment>");
+        builder.set_link(first, 0, second);
+        builder.set_link(0x40, 1, first);
+        let cn = builder.add_cn("speed", 0, 0, 0, 0, 8, 0, 0);
+        let cg = builder.add_cg(cn, 0, 0, 0, 1, 0);
+        let dg = builder.add_dg(cg, 0, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let history = wrapper.get_file_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].get_tool_id(), "Logger");
+        assert_eq!(history[0].get_tool_vendor(), "ACME");
+        assert_eq!(history[0].get_tool_version(), "2.1");
+        assert_eq!(history[0].get_user_name(), "tester");
+        assert_eq!(history[0].get_text(), "created");
+        assert_eq!(history[0].get_date_time().unwrap().to_rfc3339(), "2026-10-17T10:30:00+02:00");
+        assert_eq!(history[1].get_tool_id(), "mf4_parse");
+        assert_eq!(history[1].get_tool_vendor(), "");
+        assert_eq!(history[1].get_date_time().unwrap().to_rfc3339(), "2026-10-17T09:30:00+00:00");
+    }
+
+    #[test]
+    fn test_events() {
+        use crate::components::ev::event::{EventType, EventCause, RangeType};
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        builder.set_start_time_ns(1_792_225_800_000_000_000);
+        let cn = builder.add_cn("speed", 0, 0, 0, 0, 8, 0, 0);
+        let cg = builder.add_cg(cn, 0, 0, 0, 1, 0);
+        let dg = builder.add_dg(cg, 0, 0);
+        let begin = builder.add_ev("recording", 0, 1, 2, &[], 0, 1.0);
+        let end = builder.add_ev("recording", 0, 2, 2, &[], 1500, 0.001);
+        let marker = builder.add_ev("gear shift", 6, 0, 4, &[cn, cg], 250, 0.001);
+        let pre_trigger = builder.add_ev("pre trigger", 5, 0, 2, &[], -500, 0.001);
+        builder.set_link(marker, 0, pre_trigger);
+        builder.set_link(end, 2, begin);
+        builder.set_link(marker, 1, begin);
+        builder.set_link(begin, 0, end);
+        builder.set_link(end, 0, marker);
+        builder.set_link(0x40, 4, begin);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let events = wrapper.get_events();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[2].get_name(), "gear shift");
+        assert_eq!(events[2].get_event_type(), &EventType::Marker);
+        assert_eq!(events[2].get_cause(), &EventCause::User);
+        assert_eq!(events[2].get_range_type(), &RangeType::Point);
+        assert_eq!(wrapper.get_event_time_ns(&events[2]), Some(1_792_225_800_250_000_000));
+        assert_eq!(wrapper.get_event_parent(&events[2]).unwrap().get_offset(), begin);
+        assert_eq!(wrapper.get_event_scope_names(&events[2]), vec!["speed".to_string(), "".to_string()]);
+        let ranges = wrapper.get_event_ranges();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0].0.get_offset(), begin);
+        assert_eq!(wrapper.get_event_time_ns(ranges[0].1.unwrap()), Some(1_792_225_801_500_000_000));
+        assert_eq!(wrapper.get_event_time_ns(&events[3]), Some(1_792_225_799_500_000_000));
+        assert_eq!(events[3].get_abs_time_ns(100_000_000), None);   // before the epoch
+    }
+
+    #[test]
+    fn test_channel_hierarchy() {
+        use crate::components::ch::channelhierarchy::ChType;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let rpm = builder.add_cn("rpm", 0, 0, 1, 0, 8, 0, 0);
+        let speed = builder.add_cn("speed", 0, 0, 0, 0, 8, 0, 0);
+        builder.set_link(speed, 0, rpm);
+        let cg = builder.add_cg(speed, 0, 0, 0, 2, 0);
+        let dg = builder.add_dg(cg, 0, 0);
+        let engine = builder.add_ch("Engine", 1, 0, &[(dg, cg, rpm)]);
+        let vehicle = builder.add_ch("Vehicle", 1, 0, &[(dg, cg, speed)]);
+        builder.set_link(engine, 0, vehicle);
+        let ecu = builder.add_ch("ECU", 0, engine, &[]);
+        let not_ch = builder.add_text("not a CH block");
+        let broken = builder.add_ch("Broken", 0, not_ch, &[]);   // child link to a TX block
+        builder.set_link(ecu, 0, broken);
+        builder.set_link(0x40, 2, ecu);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let roots = wrapper.get_channel_hierarchy();
+        assert_eq!(roots.len(), 1);   // broken node is skipped and reported
+        assert_eq!(wrapper.get_warnings().len(), 1);
+        assert!(matches!(wrapper.get_warnings()[0], crate::error::Mf4Error::CorruptLink { target, .. } if target == not_ch));
+        assert_eq!(roots[0].get_name(), "ECU");
+        assert_eq!(roots[0].get_type(), &ChType::Group);
+        assert_eq!(roots[0].get_children().len(), 2);
+        assert_eq!(roots[0].get_children()[0].get_type(), &ChType::Function);
+        assert_eq!(roots[0].get_children()[1].get_elements(), &vec![(dg, cg, speed)]);
+        assert_eq!(roots[0].get_channel_paths("/"), vec!["ECU/Engine/rpm".to_string(), "ECU/Vehicle/speed".to_string()]);
+    }
+
+    #[test]
+    fn test_sample_reduction() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let speed = builder.add_cn("speed", 0, 0, 8, 0, 8, 0, 0);
+        let time = builder.add_cn("time", 2, 4, 0, 0, 64, 0, 0);
+        builder.set_link(time, 0, speed);
+        let cg = builder.add_cg(time, 0, 4, 0, 9, 1);
+        let mut records: Vec<u8> = Vec::new();
+        for (t, v) in [(0.0f64, 10u8), (0.5, 20), (1.0, 30), (1.5, 50)] {
+            records.extend_from_slice(&t.to_le_bytes());
+            records.extend_from_slice(&[v, 0]);
+        }
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let mut reduced: Vec<u8> = Vec::new();  // mean, min, max records each followed by invalidation byte
+        for (t, v) in [(0.25f64, 15u8), (0.0, 10), (0.5, 20), (1.25, 40), (1.0, 30), (1.5, 50)] {
+            reduced.extend_from_slice(&t.to_le_bytes());
+            reduced.extend_from_slice(&[v, 0]);
+        }
+        let rd = builder.add_block("RD", &[], &reduced);
+        let mut d: Vec<u8> = Vec::new();
+        d.extend_from_slice(&2u64.to_le_bytes());
+        d.extend_from_slice(&1.0f64.to_le_bytes());
+        d.extend_from_slice(&[1, 1, 0, 0, 0, 0, 0, 0]);
+        let sr = builder.add_block("SR", &[0, rd], &d);
+        builder.set_link(cg, 4, sr);
+        let file: Vec<u8> = builder.finish(dg);
+        let mut broken: Vec<u8> = file.clone();
+        broken[sr as usize + 56] = 9;   // unknown sync type
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(broken, None).unwrap();
+        assert!(matches!(wrapper.get_warnings().first(), Some(Mf4Error::Malformed { offset: Some(off), .. }) if *off == sr));
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(file, None).unwrap();
+        let series = wrapper.get_channel_reduced_data("speed").unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].interval, 1.0);
+        assert_eq!(series[0].master, vec![0.25, 1.25]);
+        assert_eq!(series[0].mean, vec![15.0, 40.0]);
+        assert_eq!(series[0].min, vec![10.0, 30.0]);
+        assert_eq!(series[0].max, vec![20.0, 50.0]);
+        let speed_data: Vec<f64> = wrapper.get_channel_data("speed").unwrap().try_into().unwrap();
+        assert_eq!(speed_data, vec![10.0, 20.0, 30.0, 50.0]);
+    }
+
+    #[test]
+    fn test_template_arrays() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        // CG template: the template group keeps record id 1, elements follow with ids 2 and 3
+        let speed = builder.add_cn("speed", 0, 0, 0, 0, 8, 0, 0);
+        let arr = builder.add_cn("arr", 0, 0, 1, 0, 8, 0, 0);
+        let ca = builder.add_ca(0, 1, 0, 0, &[2], &[], &[2, 1], &[]);
+        builder.set_link(arr, 1, ca);
+        builder.set_link(speed, 0, arr);
+        let cg_a = builder.add_cg(speed, 1, 1, 0, 2, 0);
+        let dt_a = builder.add_block("DT", &[], &[1, 5, 0, 2, 6, 10, 3, 7, 20, 2, 8, 11]);
+        let dg_a = builder.add_dg(cg_a, dt_a, 1);
+        // DG template: column oriented 2x2 array; ca_data holds the data block of each element by linear index
+        let dt_00 = builder.add_block("DT", &[], &[1, 0]);
+        let dt_10 = builder.add_block("DT", &[], &[11, 0]);
+        let dz_01 = builder.add_dz("DT", 0, 0, &[2, 0, 3, 0]);
+        let dt_11 = builder.add_block("DT", &[], &[12, 0]);
+        let map = builder.add_cn("map", 0, 0, 0, 0, 16, 0, 0);
+        let ca = builder.add_ca(0, 2, 0x40, 0, &[2, 2], &[], &[1, 1, 2, 1], &[dt_00, dt_10, dz_01, dt_11]);
+        builder.set_link(map, 1, ca);
+        let cg_b = builder.add_cg(map, 0, 0, 0, 2, 0);
+        let dg_b = builder.add_dg(cg_b, 0, 0);
+        builder.set_link(dg_a, 0, dg_b);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg_a), None).unwrap();
+        let mut names = wrapper.get_channel_names();
+        names.sort();
+        assert_eq!(names, vec!["arr[0]", "arr[1]", "map[0][0]", "map[0][1]", "map[1][0]", "map[1][1]", "speed"]);
+        let read = |name: &str| -> Vec<f64> { wrapper.get_channel_data(name).unwrap().try_into().unwrap() };
+        assert_eq!(read("speed"), vec![5.0]);
+        assert_eq!(read("arr[0]"), vec![10.0, 11.0]);
+        assert_eq!(read("arr[1]"), vec![20.0]);
+        assert_eq!(read("map[0][0]"), vec![1.0]);
+        assert_eq!(read("map[1][0]"), vec![11.0]);
+        assert_eq!(read("map[0][1]"), vec![2.0, 3.0]);
+        assert_eq!(read("map[1][1]"), vec![12.0]);
+        assert!(wrapper.get_warnings().is_empty());
+
+        // element records must not take the record id of another channel group
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let arr = builder.add_cn("arr", 0, 0, 0, 0, 8, 0, 0);
+        let ca = builder.add_ca(0, 1, 0, 0, &[1], &[], &[0], &[]);
+        builder.set_link(arr, 1, ca);
+        let cg_a = builder.add_cg(arr, 1, 0, 0, 1, 0);
+        let other = builder.add_cn("other", 0, 0, 0, 0, 8, 0, 0);
+        let cg_b = builder.add_cg(other, 2, 0, 0, 1, 0);
+        builder.set_link(cg_a, 0, cg_b);
+        let dg = builder.add_dg(cg_a, 0, 1);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        assert!(matches!(wrapper.get_warnings().first(), Some(Mf4Error::Malformed { .. })));
+    }
+
+    #[test]
+    fn test_channel_array() {
+        use crate::components::ca::channelarray::ArrayType;
+        let wrapper = Mf4Wrapper::new::<fn(f64)>(PathBuf::from("test/string_and_array.mf4"), None).unwrap();
+        let array = wrapper.get_channel_array("Channel_lookup_with_default_axis").unwrap();
+        assert_eq!(array.array_type, ArrayType::Array);
+        assert_eq!(array.shape, vec![2, 3, 4]);
+        assert_eq!(array.axes[2].values, vec![0.0, 1.0, 2.0, 3.0]);
+        assert!(array.values.iter().all(|v| v.len() == 24));
+        let element: Vec<f64> = wrapper.get_channel_data("Channel_lookup_with_default_axis[1][2][3]").unwrap().try_into().unwrap();
+        assert_eq!(array.values.len(), element.len());
+        assert_eq!(array.get(0, &[1, 2, 3]), Some(element[0]));
+        assert_eq!(array.get(0, &[2, 0, 0]), None);
+        assert!(wrapper.get_channel_array("Channel_not_exist").is_none());
+
+        // look-up table with fixed axis and axis conversion
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let curve = builder.add_cn("curve", 0, 0, 0, 0, 8, 0, 0);
+        let axis_cc = builder.add_cc("", 1, &[], &[0.0, 10.0]);
+        let ca = builder.add_ca(2, 0, 0x30, 1, &[3], &[1.0, 2.0, 3.0], &[], &[axis_cc]);
+        builder.set_link(curve, 1, ca);
+        let cg = builder.add_cg(curve, 0, 2, 0, 3, 0);
+        let dt = builder.add_block("DT", &[], &[5, 6, 7, 8, 9, 10]);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let curve = wrapper.get_channel_array("curve").unwrap();
+        assert_eq!(curve.array_type, ArrayType::LookUp);
+        assert_eq!(curve.axes[0].values, vec![10.0, 20.0, 30.0]);
+        assert!(curve.axes[0].conversion.is_some());
+        assert_eq!(curve.values, vec![vec![5.0, 6.0, 7.0], vec![8.0, 9.0, 10.0]]);
+    }
+
+    #[test]
+    fn test_md_comment() {
+        use crate::components::md::metadata::CommentKind;
+        let wrapper = Mf4Wrapper::new::<fn(f64)>(PathBuf::from("test/1.mf4"), None).unwrap();
+        let header = wrapper.get_header_comment();
+        assert_eq!(header.get_kind(), &CommentKind::HD);
+        assert_eq!(header.get_author(), Some("UserName"));
+        assert_eq!(header.get_department(), Some("CompanyName"));
+        assert_eq!(header.get_project(), Some("P2016_09_AE_MCD_2MC_BS_V1_7_1_main"));
+        assert_eq!(header.get_subject(), Some("TestVehicle"));
+        assert_eq!(header.get_field("time_source"), Some("INCA PC Reference Time"));
+        assert!(header.get_text().starts_with("Date: 22.11.2018"));
+        let ChannelLink(cn, _, _) = wrapper.get_channel_link("ASAM.M.SCALAR.UBYTE.TAB_VERB_NO_DEFAULT_VALUE").unwrap();
+        let md = cn.get_md_comment();
+        assert_eq!(md.get_kind(), &CommentKind::CN);
+        assert_eq!(md.get_text(), "Scalar measurement with verbal conversion");
+        assert_eq!(md.get_display_name(), Some("ASAM.M.SCALAR.UBYTE.TAB_VERB_NO_DEFAULT_VALUE"));
+        assert_eq!(md.get_field("raster"), Some("0.001000"));
+    }
+
+    #[test]
+    fn test_header() {
+        use crate::components::hd::header::TimeClass;
+        let wrapper = Mf4Wrapper::new::<fn(f64)>(PathBuf::from("test/1.mf4"), None).unwrap();
+        let header = wrapper.get_header();
+        assert_eq!(header.get_program_id(), "TGT 15.0");
+        assert!(!header.is_local_time());
+        assert!(header.has_tz_offset());
+        assert_eq!(header.get_tz_offset_min(), 60);
+        assert_eq!(header.get_time_class(), &TimeClass::LocalPcReferenceTime);
+        assert_eq!(header.get_start_date_time().unwrap().to_rfc3339(), "2018-11-22T15:26:35.439737199+01:00");
+        assert_eq!(header.get_start_angle_rad(), None);
+
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        builder.set_start_time_ns(1_792_225_800_000_000_000);
+        let pos = 0x40 + 24 + 6 * 8 + 8;
+        builder.buf[pos..pos+2].copy_from_slice(&(-300i16).to_le_bytes());
+        builder.buf[pos+2..pos+4].copy_from_slice(&60i16.to_le_bytes());
+        builder.buf[pos+4..pos+8].copy_from_slice(&[0x02, 16, 0x02, 0]);
+        builder.buf[pos+16..pos+24].copy_from_slice(&125.5f64.to_le_bytes());
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(0), None).unwrap();
+        let header = wrapper.get_header();
+        assert_eq!(header.get_time_class(), &TimeClass::ExternalAbsoluteSynchronized);
+        assert_eq!(header.get_start_date_time().unwrap().to_rfc3339(), "2026-10-17T04:30:00-04:00");
+        assert_eq!(header.get_start_angle_rad(), None);
+        assert_eq!(header.get_start_distance_m(), Some(125.5));
+    }
+
+    #[test]
+    fn test_abs_time() {
+        fn build(start_ns: u64, name: &str, time_flags: u8, times: [f64; 3]) -> Vec<u8> {
+            let mut builder = crate::test_utils::Mf4Builder::new();
+            builder.set_start_time_ns(start_ns);
+            let pos = 0x40 + 24 + 6 * 8 + 8;
+            builder.buf[pos..pos+2].copy_from_slice(&120i16.to_le_bytes());
+            builder.buf[pos+2..pos+4].copy_from_slice(&60i16.to_le_bytes());
+            builder.buf[pos+4] = time_flags;
+            let value = builder.add_cn(name, 0, 0, 8, 0, 8, 0, 0);
+            let time = builder.add_cn("time", 2, 4, 0, 0, 64, 0, 0);
+            builder.set_link(time, 0, value);
+            let cg = builder.add_cg(time, 0, 3, 0, 9, 0);
+            let mut records: Vec<u8> = Vec::new();
+            for (t, v) in times.into_iter().zip([1u8, 2, 3]) {
+                records.extend_from_slice(&t.to_le_bytes());
+                records.push(v);
+            }
+            let dt = builder.add_block("DT", &[], &records);
+            let dg = builder.add_dg(cg, dt, 0);
+            builder.finish(dg)
+        }
+        let start: u64 = 1_792_225_800_000_000_000;
+        let times = [0.0, 0.5, 1.25];
+        let wrapper_a = Mf4Wrapper::from_bytes::<fn(f64)>(build(start, "a", 0x02, times), None).unwrap();
+        let wrapper_b = Mf4Wrapper::from_bytes::<fn(f64)>(build(start + 2_000_000_000, "b", 0x02, times), None).unwrap();
+        assert_eq!(wrapper_a.get_channel_abs_time_ns("a").unwrap(), vec![start, start + 500_000_000, start + 1_250_000_000]);
+        let date_time = wrapper_a.get_channel_abs_date_time("a").unwrap();
+        assert_eq!(date_time[2].to_rfc3339(), "2026-10-17T11:30:01.250+03:00");
+        // local start time: offset fields are not valid, so UTC is unknown
+        let wrapper_local = Mf4Wrapper::from_bytes::<fn(f64)>(build(start, "local", 0x01, times), None).unwrap();
+        assert!(wrapper_local.get_channel_abs_time_ns("local").is_none());
+        assert!(wrapper_local.get_channel_time_relative_to("local", &wrapper_a).is_none());
+        // samples before the epoch can not be represented
+        let wrapper_early = Mf4Wrapper::from_bytes::<fn(f64)>(build(1_000_000_000, "early", 0x02, [-2.0, 0.0, 1.0]), None).unwrap();
+        assert!(wrapper_early.get_channel_abs_time_ns("early").is_none());
+        let wrapper_negative = Mf4Wrapper::from_bytes::<fn(f64)>(build(start, "negative", 0x02, [-2.0, 0.0, 1.0]), None).unwrap();
+        assert_eq!(wrapper_negative.get_channel_time_relative_to("negative", &wrapper_a).unwrap(), vec![-2.0, 0.0, 1.0]);
+        assert_eq!(wrapper_b.get_channel_time_relative_to("b", &wrapper_a).unwrap(), vec![2.0, 2.5, 3.25]);
+        assert_eq!(wrapper_a.get_channel_time_relative_to("a", &wrapper_b).unwrap(), vec![-2.0, -1.5, -0.75]);
+        assert!(wrapper_a.get_channel_abs_time_ns("time").is_none());   // master channel itself is not in channel list
+    }
+
+    #[test]
+    fn test_sync_and_vlsc_channels() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let video = builder.add_at("camera.mp4", "video/mp4", 0, &[], &[]);
+        builder.set_link(0x40, 3, video);
+        // values without length prefix and with padding between them; the last value is referenced twice
+        let mut sd: Vec<u8> = Vec::new();
+        for (text, padding) in [("idle", 4), ("start", 3), ("stop", 8)] {
+            sd.extend_from_slice(text.as_bytes());
+            sd.extend_from_slice(&vec![0xFFu8; padding]);
+        }
+        let sd = builder.add_block("SD", &[], &sd);
+        let label_size = builder.add_cn("label_size", 0, 0, 20, 0, 16, 0, 0);
+        let label = builder.add_cn("label", 7, 7, 12, 0, 64, 0, 0);
+        builder.set_link(label, 5, sd);
+        builder.set_link(label, 1, label_size);   // lengths of the values are given by the size channel
+        builder.set_link(label, 0, label_size);
+        let frame = builder.add_cn("frame", 4, 0, 8, 0, 32, 0, 0);
+        builder.set_link(frame, 5, video);
+        builder.buf[frame as usize + 24 + 8 * 8 + 1] = 1;   // stream position is time based
+        builder.set_link(frame, 0, label);
+        let time = builder.add_cn("time", 2, 4, 0, 0, 64, 0, 0);
+        builder.set_link(time, 0, frame);
+        let cg = builder.add_cg(time, 0, 4, 0, 22, 0);
+        let mut records: Vec<u8> = Vec::new();
+        for (t, f, o, n) in [(0.0f64, 10u32, 8u64, 5u16), (0.04, 11, 0, 4), (0.08, 12, 16, 4), (0.12, 13, 16, 4)] {
+            records.extend_from_slice(&t.to_le_bytes());
+            records.extend_from_slice(&f.to_le_bytes());
+            records.extend_from_slice(&o.to_le_bytes());
+            records.extend_from_slice(&n.to_le_bytes());
+        }
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let link = wrapper.get_channel_link("frame").unwrap();
+        assert!(link.get_channel().is_sync_channel());
+        assert_eq!(link.get_channel().get_sync_attachment(), Some(video));
+        let at = wrapper.get_sync_channel_attachment("frame").unwrap();
+        assert_eq!(at.get_file_name(), "camera.mp4");
+        assert_eq!(at.get_mime_type(), "video/mp4");
+        assert_eq!(wrapper.get_sync_channel_positions("frame").unwrap(), vec![(0.0, 10.0), (0.04, 11.0), (0.08, 12.0), (0.12, 13.0)]);
+        assert!(wrapper.get_sync_channel_positions("label").is_none());
+        assert!(wrapper.get_sync_channel_attachment("label").is_none());
+        assert_eq!(wrapper.get_channel_data("label").unwrap(), 
+                   crate::DataValue::STRINGS(vec!["start".to_string(), "idle".to_string(), "stop".to_string(), "stop".to_string()]));
+        assert_eq!(wrapper.get_channel_data("label_size").unwrap(), crate::DataValue::REAL(vec![5.0, 4.0, 4.0, 4.0]));
+        drop(wrapper);
+    }
+
+    #[test]
+    fn test_mime_and_canopen_channels() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let mut sd: Vec<u8> = Vec::new();
+        for payload in [&b"\x89PNG-1"[..], &b"\x89PNG-22"[..]] {
+            sd.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            sd.extend_from_slice(payload);
+        }
+        let sd = builder.add_block("SD", &[], &sd);
+        let image = builder.add_cn("image", 1, 11, 13, 0, 64, 0, 0);
+        builder.set_link(image, 5, sd);
+        let unit = builder.add_text("image/png");
+        builder.set_link(image, 6, unit);
+        let time = builder.add_cn("can_time", 0, 14, 7, 0, 48, 0, 0);
+        builder.set_link(time, 0, image);
+        let date = builder.add_cn("can_date", 0, 13, 0, 0, 56, 0, 0);
+        builder.set_link(date, 0, time);
+        let cg = builder.add_cg(date, 0, 2, 0, 21, 0);
+        let mut records: Vec<u8> = Vec::new();
+        for (minute, days, offset) in [(45u8, 1u16, 0u64), (46, 2, 10)] {
+            records.extend_from_slice(&[0x2a, 0x76, minute, 0x8d, 0x9d, 0x02, 0x18]);
+            records.extend_from_slice(&1500u32.to_le_bytes());
+            records.extend_from_slice(&days.to_le_bytes());
+            records.extend_from_slice(&offset.to_le_bytes());
+        }
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let dates: Vec<chrono::NaiveDateTime> = wrapper.get_channel_data("can_date").unwrap().try_into().unwrap();
+        assert_eq!(dates.iter().map(|d| d.to_string()).collect::<Vec<_>>(), 
+                   vec!["2024-02-29 13:45:30.250", "2024-02-29 13:46:30.250"]);
+        let times: Vec<chrono::NaiveDateTime> = wrapper.get_channel_data("can_time").unwrap().try_into().unwrap();
+        assert_eq!(times[1].to_string(), "1984-01-03 00:00:01.500");
+        assert_eq!(wrapper.get_channel_link("image").unwrap().get_channel().get_mime_type(), Some("image/png".to_string()));
+        assert_eq!(wrapper.get_channel_data("image").unwrap(), 
+                   crate::DataValue::MIME("image/png".to_string(), vec![b"\x89PNG-1".to_vec(), b"\x89PNG-22".to_vec()]));
+        drop(wrapper);
+    }
+
+    #[test]
+    fn test_complex_channels() {
+        use crate::error::Mf4Error;
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let bins = builder.add_cn("bins", 0, 16, 8, 0, 128, 0, 0);
+        let spectrum = builder.add_cn("spectrum", 0, 15, 0, 0, 64, 0, 0);
+        let cc = builder.add_cc("", 1, &[], &[1.0, 2.0]);   // phys = 1 + 2 * raw
+        builder.set_link(spectrum, 4, cc);
+        builder.set_link(spectrum, 0, bins);
+        // same samples with a value to text conversion and with a formula which can not be evaluated
+        let labels = builder.add_cn("labels", 0, 15, 0, 0, 64, 0, 0);
+        let label = builder.add_text("half");
+        let text_cc = builder.add_cc("", 7, &[label, 0], &[0.5]);
+        builder.set_link(labels, 4, text_cc);
+        builder.set_link(bins, 0, labels);
+        let broken = builder.add_cn("broken", 0, 15, 0, 0, 64, 0, 0);
+        let formula = builder.add_text("X +");
+        let broken_cc = builder.add_cc("", 3, &[formula], &[]);
+        builder.set_link(broken, 4, broken_cc);
+        builder.set_link(labels, 0, broken);
+        let cg = builder.add_cg(spectrum, 0, 2, 0, 24, 0);
+        let mut records: Vec<u8> = Vec::new();
+        for (re, im) in [(0.5f32, -1.0f32), (2.0, 0.25)] {
+            records.extend_from_slice(&re.to_le_bytes());
+            records.extend_from_slice(&im.to_le_bytes());
+            records.extend_from_slice(&(re as f64).to_be_bytes());
+            records.extend_from_slice(&(im as f64).to_be_bytes());
+        }
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let file: Vec<u8> = builder.finish(dg);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(file.clone(), None).unwrap();
+        let spectrum: Vec<(f64, f64)> = wrapper.get_channel_data("spectrum").unwrap().try_into().unwrap();
+        assert_eq!(spectrum, vec![(2.0, -1.0), (5.0, 1.5)]);
+        assert_eq!(wrapper.get_channel_raw_data("spectrum").unwrap(), crate::DataValue::COMPLEX(vec![(0.5, -1.0), (2.0, 0.25)]));
+        let bins: Vec<(f64, f64)> = wrapper.get_channel_data("bins").unwrap().try_into().unwrap();
+        assert_eq!(bins, vec![(0.5, -1.0), (2.0, 0.25)]);
+        assert!(wrapper.get_channel_data("labels").is_none());
+        for name in ["labels", "broken"] {
+            let ChannelLink(cn, cg, dg) = wrapper.get_channel_link(name).unwrap();
+            let err = cn.get_data(&mut Cursor::new(file.as_slice()), dg, cg).err().unwrap();
+            assert!(matches!(err, Mf4Error::Conversion { .. }));
+        }
+        drop(wrapper);
+    }
+
+    #[test]
+    fn test_string_encodings() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let mut sd: Vec<u8> = Vec::new();
+        for payload in [&[0xff, 0xfe, 0xd6, 0x00, 0x6c, 0x00][..], &[0xfe, 0xff, 0x00, 0xc4, 0x00, 0x6c][..]] {
+            sd.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            sd.extend_from_slice(payload);
+        }
+        let sd = builder.add_block("SD", &[], &sd);
+        let note = builder.add_cn("Notiz", 1, 8, 12, 0, 64, 0, 0);
+        builder.set_link(note, 5, sd);
+        let wide = builder.add_cn("Gang", 0, 9, 6, 0, 48, 0, 0);
+        builder.set_link(wide, 0, note);
+        let state = builder.add_cn("Öltemperatur Zustand", 0, 6, 0, 0, 48, 0, 0);
+        builder.set_link(state, 0, wide);
+        let cg = builder.add_cg(state, 0, 2, 0, 20, 0);
+        let mut records: Vec<u8> = Vec::new();
+        for (latin1, utf16, offset) in [(&b"gr\xfcn\0\0"[..], "Rück", 0u64), (&b"hei\xdf\0\0"[..], "Eins", 10)] {
+            records.extend_from_slice(latin1);
+            records.extend(utf16.encode_utf16().take(3).flat_map(|u| u.to_be_bytes()));
+            records.extend_from_slice(&offset.to_le_bytes());
+        }
+        let dt = builder.add_block("DT", &[], &records);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let strings = |name: &str| -> Vec<String> { wrapper.get_channel_data(name).unwrap().try_into().unwrap() };
+        assert_eq!(strings("Öltemperatur Zustand"), vec!["grün", "heiß"]);
+        assert_eq!(strings("Gang"), vec!["Rüc", "Ein"]);
+        assert_eq!(strings("Notiz"), vec!["Öl", "Äl"]);   // byte order marks of UTF-16 values
+        drop(wrapper);
+    }
+
+    #[test]
+    fn test_motorola_bit_fields() {
+        let mut builder = crate::test_utils::Mf4Builder::new();
+        let intel = builder.add_cn("intel_u12", 0, 0, 0, 4, 12, 0, 0);
+        let signed = builder.add_cn("motorola_s10", 0, 3, 1, 6, 10, 0, 0);
+        builder.set_link(signed, 0, intel);
+        let unsigned = builder.add_cn("motorola_u12", 0, 1, 0, 4, 12, 0, 0);
+        builder.set_link(unsigned, 0, signed);
+        let wide = builder.add_cn("motorola_u18", 0, 1, 0, 6, 18, 0, 0);
+        builder.set_link(wide, 0, unsigned);
+        let cg = builder.add_cg(wide, 0, 2, 0, 3, 0);
+        let dt = builder.add_block("DT", &[], &[0x12, 0x34, 0x56, 0xff, 0xf8, 0x00]);
+        let dg = builder.add_dg(cg, dt, 0);
+        let wrapper = Mf4Wrapper::from_bytes::<fn(f64)>(builder.finish(dg), None).unwrap();
+        let values = |name: &str| -> Vec<f64> { wrapper.get_channel_data(name).unwrap().try_into().unwrap() };
+        assert_eq!(values("motorola_u12"), vec![291.0, 4095.0]);   // 0x123, 0xfff
+        assert_eq!(values("motorola_s10"), vec![209.0, -32.0]);   // 0x0d1, 0x3e0
+        assert_eq!(values("motorola_u18"), vec![18641.0, 262112.0]);   // 0x123456 >> 6, 0xfff800 >> 6
+        assert_eq!(values("intel_u12"), vec![833.0, 3983.0]);   // 0x341, 0xf8f
+        drop(wrapper);
+    }
+
     #[test]
     fn test_mdf_wrapper_new() {
         let wrapper = Mf4Wrapper::new::<fn(f64)>(PathBuf::from("test/1.mf4"), None).unwrap();
         println!("{:?}", wrapper.get_channel_names());
+        assert_eq!(wrapper.get_file_history()[0].get_tool_vendor(), "ETAS GmbH");
         let _ = wrapper.get_channel_data("$CalibrationLog").unwrap();
                                             //.unwrap_or(crate::data_serde::DataValue::CHAR("Error".to_string()));
         //println!("{:?}", channel_data);
@@ -1020,4 +2499,6 @@ pub mod parser_test {
         let channel_data = wrapper.get_channel_data("ASAM.M.SCALAR.UBYTE.HYPERBOLIC").unwrap();
         println!("{:?}", channel_data);
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test_utils;
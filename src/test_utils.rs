@@ -0,0 +1,212 @@
+// helpers to assemble small mf4 files in memory for features without test files
+pub struct Mf4Builder {
+    pub buf: Vec<u8>,
+}
+
+impl Default for Mf4Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mf4Builder {
+    pub fn new() -> Self {
+        let mut buf: Vec<u8> = Vec::new();
+        buf.extend_from_slice(b"MDF     4.10    TEST    ");
+        buf.resize(28, 0);
+        buf.extend_from_slice(&410u16.to_le_bytes());
+        buf.resize(64, 0);
+        let mut builder = Self { buf };
+        builder.add_block("HD", &[0; 6], &[0u8; 32]);   // always at 0x40
+        builder
+    }
+
+    pub fn add_block(&mut self, id: &str, links: &[u64], data: &[u8]) -> u64 {
+        let offset = self.buf.len() as u64;
+        let blk_len = 24 + links.len() * 8 + data.len();
+        self.buf.extend_from_slice(format!("##{}", id).as_bytes());
+        self.buf.extend_from_slice(&[0u8; 4]);
+        self.buf.extend_from_slice(&(blk_len as u64).to_le_bytes());
+        self.buf.extend_from_slice(&(links.len() as u64).to_le_bytes());
+        links.iter().for_each(|l| self.buf.extend_from_slice(&l.to_le_bytes()));
+        self.buf.extend_from_slice(data);
+        self.buf.resize(self.buf.len().div_ceil(8) * 8, 0);   // blocks are 8 bytes aligned
+        offset
+    }
+
+    pub fn set_link(&mut self, block: u64, index: usize, target: u64) {
+        let pos = block as usize + 24 + index * 8;
+        self.buf[pos..pos+8].copy_from_slice(&target.to_le_bytes());
+    }
+
+    pub fn add_text(&mut self, text: &str) -> u64 {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        self.add_block("TX", &[], &data)
+    }
+
+    pub fn add_dg(&mut self, cg_first: u64, data: u64, rec_id_size: u8) -> u64 {
+        let mut d = vec![rec_id_size];
+        d.resize(8, 0);
+        self.add_block("DG", &[0, cg_first, data, 0], &d)
+    }
+
+    pub fn add_cg(&mut self, cn_first: u64, record_id: u64, cycle_count: u64, flags: u16, data_bytes: u32, inval_bytes: u32) -> u64 {
+        let mut d: Vec<u8> = Vec::new();
+        d.extend_from_slice(&record_id.to_le_bytes());
+        d.extend_from_slice(&cycle_count.to_le_bytes());
+        d.extend_from_slice(&flags.to_le_bytes());
+        d.extend_from_slice(&[0u8; 6]);
+        d.extend_from_slice(&data_bytes.to_le_bytes());
+        d.extend_from_slice(&inval_bytes.to_le_bytes());
+        self.add_block("CG", &[0, cn_first, 0, 0, 0, 0, 0], &d)   // link 4 is cg_sr_first, last link is cg_cg_master
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_cn(&mut self, name: &str, cn_type: u8, data_type: u8, byte_offset: u32, bit_offset: u8, bit_count: u32,
+                  flags: u32, inval_bit_pos: u32) -> u64 {
+        let tx = self.add_text(name);
+        let mut d: Vec<u8> = vec![cn_type, if cn_type == 2 { 1 } else { 0 }, data_type, bit_offset];
+        d.extend_from_slice(&byte_offset.to_le_bytes());
+        d.extend_from_slice(&bit_count.to_le_bytes());
+        d.extend_from_slice(&flags.to_le_bytes());
+        d.extend_from_slice(&inval_bit_pos.to_le_bytes());
+        d.resize(72, 0);
+        self.add_block("CN", &[0, 0, tx, 0, 0, 0, 0, 0], &d)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_ca(&mut self, ca_type: u8, storage: u8, flags: u32, byte_offset_base: i32, dims: &[u64], extra: &[f64],
+                  cycle_counts: &[u64], links: &[u64]) -> u64 {
+        // extra: fixed axis values; links: everything after ca_composition
+        let mut d: Vec<u8> = vec![ca_type, storage];
+        d.extend_from_slice(&(dims.len() as u16).to_le_bytes());
+        d.extend_from_slice(&flags.to_le_bytes());
+        d.extend_from_slice(&byte_offset_base.to_le_bytes());
+        d.extend_from_slice(&[0u8; 4]);
+        dims.iter().for_each(|v| d.extend_from_slice(&v.to_le_bytes()));
+        extra.iter().for_each(|v| d.extend_from_slice(&v.to_le_bytes()));
+        cycle_counts.iter().for_each(|v| d.extend_from_slice(&v.to_le_bytes()));
+        let mut all_links: Vec<u64> = vec![0];
+        all_links.extend_from_slice(links);
+        self.add_block("CA", &all_links, &d)
+    }
+
+    pub fn add_cc(&mut self, name: &str, cc_type: u8, refs: &[u64], vals: &[f64]) -> u64 {
+        let tx = if name.is_empty() { 0 } else { self.add_text(name) };
+        let mut links: Vec<u64> = vec![tx, 0, 0, 0];
+        links.extend_from_slice(refs);
+        let mut d: Vec<u8> = vec![cc_type, 0, 0, 0];
+        d.extend_from_slice(&(refs.len() as u16).to_le_bytes());
+        d.extend_from_slice(&(vals.len() as u16).to_le_bytes());
+        d.extend_from_slice(&[0u8; 16]);
+        vals.iter().for_each(|v| d.extend_from_slice(&v.to_le_bytes()));
+        self.add_block("CC", &links, &d)
+    }
+
+    pub fn add_dz(&mut self, org_block_type: &str, zip_type: u8, zip_parameter: u32, data: &[u8]) -> u64 {
+        use flate2::{write::ZlibEncoder, Compression};
+        use std::io::Write;
+        let mut to_compress: Vec<u8> = data.to_vec();
+        if zip_type == 1 {  // transpose data as a rows x zip_parameter matrix; remaining bytes are kept
+            let columns = zip_parameter as usize;
+            let rows = data.len() / columns;
+            for col in 0..columns {
+                for row in 0..rows {
+                    to_compress[col * rows + row] = data[row * columns + col];
+                }
+            }
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&to_compress).unwrap();
+        let compressed: Vec<u8> = encoder.finish().unwrap();
+        let mut d: Vec<u8> = org_block_type.as_bytes().to_vec();
+        d.extend_from_slice(&[zip_type, 0]);
+        d.extend_from_slice(&zip_parameter.to_le_bytes());
+        d.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        d.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        d.extend_from_slice(&compressed);
+        self.add_block("DZ", &[], &d)
+    }
+
+    pub fn add_at(&mut self, file_name: &str, mime_type: &str, flags: u16, payload: &[u8], original: &[u8]) -> u64 {
+        let (name, mime) = (self.add_text(file_name), self.add_text(mime_type));
+        let mut d: Vec<u8> = Vec::new();
+        d.extend_from_slice(&flags.to_le_bytes());
+        d.extend_from_slice(&[0u8; 6]);
+        d.extend_from_slice(&md5::compute(original).0);
+        d.extend_from_slice(&(original.len() as u64).to_le_bytes());
+        d.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        d.extend_from_slice(payload);
+        self.add_block("AT", &[0, name, mime, 0], &d)
+    }
+
+    pub fn add_fh(&mut self, time_ns: u64, tz_offset_min: i16, dst_offset_min: i16, time_flags: u8, comment: &str) -> u64 {
+        let mut md: Vec<u8> = comment.as_bytes().to_vec();
+        md.push(0);
+        let md = self.add_block("MD", &[], &md);
+        let mut d: Vec<u8> = Vec::new();
+        d.extend_from_slice(&time_ns.to_le_bytes());
+        d.extend_from_slice(&tz_offset_min.to_le_bytes());
+        d.extend_from_slice(&dst_offset_min.to_le_bytes());
+        d.extend_from_slice(&[time_flags, 0, 0, 0]);
+        self.add_block("FH", &[0, md], &d)
+    }
+
+    pub fn set_start_time_ns(&mut self, time_ns: u64) {
+        let pos = 0x40 + 24 + 6 * 8;   // hd_start_time_ns
+        self.buf[pos..pos+8].copy_from_slice(&time_ns.to_le_bytes());
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_ev(&mut self, name: &str, ev_type: u8, range_type: u8, cause: u8, scope: &[u64],
+                  sync_base: i64, sync_factor: f64) -> u64 {
+        let tx = self.add_text(name);
+        let mut links: Vec<u64> = vec![0, 0, 0, tx, 0];
+        links.extend_from_slice(scope);
+        let mut d: Vec<u8> = vec![ev_type, 1, range_type, cause, 0, 0, 0, 0];
+        d.extend_from_slice(&(scope.len() as u32).to_le_bytes());
+        d.extend_from_slice(&[0u8; 4]);
+        d.extend_from_slice(&sync_base.to_le_bytes());
+        d.extend_from_slice(&sync_factor.to_le_bytes());
+        self.add_block("EV", &links, &d)
+    }
+
+    pub fn add_ch(&mut self, name: &str, ch_type: u8, first_child: u64, elements: &[(u64, u64, u64)]) -> u64 {
+        let tx = self.add_text(name);
+        let mut links: Vec<u64> = vec![0, first_child, tx, 0];
+        elements.iter().for_each(|(dg, cg, cn)| links.extend_from_slice(&[*dg, *cg, *cn]));
+        let mut d: Vec<u8> = Vec::new();
+        d.extend_from_slice(&(elements.len() as u32).to_le_bytes());
+        d.extend_from_slice(&[ch_type, 0, 0, 0]);
+        self.add_block("CH", &links, &d)
+    }
+
+    pub fn finish(&mut self, dg_first: u64) -> Vec<u8> {
+        self.set_link(0x40, 0, dg_first);
+        self.buf.clone()
+    }
+}
+
+// scratch directory for tests that need real files; removed when dropped, also if the test fails
+pub struct TempDir {
+    path: std::path::PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("mf4_parse_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}